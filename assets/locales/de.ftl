@@ -0,0 +1,62 @@
+# German translations.
+
+app-title = Bevy-Rechner
+
+## Buttons, as shown in the tooltips and spoken by the screen readers
+
+button-clear = löschen
+button-invert = Vorzeichen wechseln
+button-percent = Prozent
+button-divide = geteilt durch
+button-multiply = mal
+button-subtract = minus
+button-add = plus
+button-equals = gleich
+button-decimal = Komma
+
+## Settings
+
+setting-precision = Genauigkeit
+setting-rounding = Rundung
+setting-notation = Notation
+setting-input-length = Max. Eingabe
+setting-theme = Design
+setting-locale = Zahlenformat
+setting-language = Sprache
+setting-clicks = Tastenklicks
+setting-volume = Klicklautstärke
+setting-vibration = Vibration
+setting-on = an
+setting-off = aus
+setting-auto = automatisch
+
+## Setting values
+
+language-name = Deutsch
+notation-auto = auto
+notation-fix = fix{ $decimals }
+notation-sci = wiss{ $decimals }
+notation-eng = tech{ $decimals }
+rounding-half-even = halb-gerade
+rounding-half-up = halb-auf
+rounding-half-down = halb-ab
+rounding-toward-zero = gegen-null
+rounding-floor = abrunden
+rounding-ceiling = aufrunden
+
+## Display indicators
+
+indicator-precision = { $notation } { $decimals }NK { $rounding }
+
+## Panels
+
+tape-title = Papierstreifen
+tape-note = Notiz
+history-csv = CSV
+history-json = JSON
+history-import = Importieren
+
+## Messages
+
+error-calculation = Berechnung nicht möglich
+speech-voice-on = Sprachausgabe an
@@ -0,0 +1,62 @@
+# English translations, the fallback of the other languages.
+
+app-title = Bevy Calculator
+
+## Buttons, as shown in the tooltips and spoken by the screen readers
+
+button-clear = clear
+button-invert = toggle sign
+button-percent = percent
+button-divide = divide
+button-multiply = multiply
+button-subtract = subtract
+button-add = add
+button-equals = equals
+button-decimal = decimal point
+
+## Settings
+
+setting-precision = Precision
+setting-rounding = Rounding
+setting-notation = Notation
+setting-input-length = Max input
+setting-theme = Theme
+setting-locale = Number format
+setting-language = Language
+setting-clicks = Key clicks
+setting-volume = Click volume
+setting-vibration = Vibration
+setting-on = on
+setting-off = off
+setting-auto = auto
+
+## Setting values
+
+language-name = English
+notation-auto = auto
+notation-fix = fix{ $decimals }
+notation-sci = sci{ $decimals }
+notation-eng = eng{ $decimals }
+rounding-half-even = half-even
+rounding-half-up = half-up
+rounding-half-down = half-down
+rounding-toward-zero = toward-zero
+rounding-floor = floor
+rounding-ceiling = ceiling
+
+## Display indicators
+
+indicator-precision = { $notation } { $decimals }DP { $rounding }
+
+## Panels
+
+tape-title = Tape
+tape-note = Note
+history-csv = CSV
+history-json = JSON
+history-import = Import
+
+## Messages
+
+error-calculation = Unable to calculate
+speech-voice-on = voice on
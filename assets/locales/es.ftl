@@ -0,0 +1,62 @@
+# Spanish translations.
+
+app-title = Calculadora Bevy
+
+## Buttons, as shown in the tooltips and spoken by the screen readers
+
+button-clear = borrar
+button-invert = cambiar signo
+button-percent = porcentaje
+button-divide = dividir
+button-multiply = multiplicar
+button-subtract = restar
+button-add = sumar
+button-equals = igual
+button-decimal = coma decimal
+
+## Settings
+
+setting-precision = Precisión
+setting-rounding = Redondeo
+setting-notation = Notación
+setting-input-length = Entrada máx.
+setting-theme = Tema
+setting-locale = Formato numérico
+setting-language = Idioma
+setting-clicks = Clics de teclas
+setting-volume = Volumen de clics
+setting-vibration = Vibración
+setting-on = sí
+setting-off = no
+setting-auto = automático
+
+## Setting values
+
+language-name = Español
+notation-auto = auto
+notation-fix = fij{ $decimals }
+notation-sci = cie{ $decimals }
+notation-eng = ing{ $decimals }
+rounding-half-even = medio-par
+rounding-half-up = medio-arriba
+rounding-half-down = medio-abajo
+rounding-toward-zero = hacia-cero
+rounding-floor = piso
+rounding-ceiling = techo
+
+## Display indicators
+
+indicator-precision = { $notation } { $decimals }DEC { $rounding }
+
+## Panels
+
+tape-title = Cinta
+tape-note = Nota
+history-csv = CSV
+history-json = JSON
+history-import = Importar
+
+## Messages
+
+error-calculation = No se puede calcular
+speech-voice-on = voz activada
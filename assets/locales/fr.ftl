@@ -0,0 +1,62 @@
+# French translations.
+
+app-title = Calculatrice Bevy
+
+## Buttons, as shown in the tooltips and spoken by the screen readers
+
+button-clear = effacer
+button-invert = changer de signe
+button-percent = pourcentage
+button-divide = diviser
+button-multiply = multiplier
+button-subtract = soustraire
+button-add = ajouter
+button-equals = égal
+button-decimal = virgule
+
+## Settings
+
+setting-precision = Précision
+setting-rounding = Arrondi
+setting-notation = Notation
+setting-input-length = Saisie max.
+setting-theme = Thème
+setting-locale = Format des nombres
+setting-language = Langue
+setting-clicks = Clics des touches
+setting-volume = Volume des clics
+setting-vibration = Vibration
+setting-on = activé
+setting-off = désactivé
+setting-auto = auto
+
+## Setting values
+
+language-name = Français
+notation-auto = auto
+notation-fix = fix{ $decimals }
+notation-sci = sci{ $decimals }
+notation-eng = ing{ $decimals }
+rounding-half-even = demi-pair
+rounding-half-up = demi-haut
+rounding-half-down = demi-bas
+rounding-toward-zero = vers-zéro
+rounding-floor = plancher
+rounding-ceiling = plafond
+
+## Display indicators
+
+indicator-precision = { $notation } { $decimals }DÉC { $rounding }
+
+## Panels
+
+tape-title = Bande
+tape-note = Note
+history-csv = CSV
+history-json = JSON
+history-import = Importer
+
+## Messages
+
+error-calculation = Calcul impossible
+speech-voice-on = voix activée
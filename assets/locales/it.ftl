@@ -0,0 +1,62 @@
+# Italian translations.
+
+app-title = Calcolatrice Bevy
+
+## Buttons, as shown in the tooltips and spoken by the screen readers
+
+button-clear = cancella
+button-invert = cambia segno
+button-percent = percentuale
+button-divide = diviso
+button-multiply = per
+button-subtract = meno
+button-add = più
+button-equals = uguale
+button-decimal = virgola
+
+## Settings
+
+setting-precision = Precisione
+setting-rounding = Arrotondamento
+setting-notation = Notazione
+setting-input-length = Input max.
+setting-theme = Tema
+setting-locale = Formato numerico
+setting-language = Lingua
+setting-clicks = Clic dei tasti
+setting-volume = Volume dei clic
+setting-vibration = Vibrazione
+setting-on = sì
+setting-off = no
+setting-auto = automatica
+
+## Setting values
+
+language-name = Italiano
+notation-auto = auto
+notation-fix = fis{ $decimals }
+notation-sci = sci{ $decimals }
+notation-eng = ing{ $decimals }
+rounding-half-even = metà-pari
+rounding-half-up = metà-su
+rounding-half-down = metà-giù
+rounding-toward-zero = verso-zero
+rounding-floor = difetto
+rounding-ceiling = eccesso
+
+## Display indicators
+
+indicator-precision = { $notation } { $decimals }DEC { $rounding }
+
+## Panels
+
+tape-title = Rotolo
+tape-note = Nota
+history-csv = CSV
+history-json = JSON
+history-import = Importa
+
+## Messages
+
+error-calculation = Impossibile calcolare
+speech-voice-on = voce attivata
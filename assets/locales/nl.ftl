@@ -0,0 +1,62 @@
+# Dutch translations.
+
+app-title = Bevy-rekenmachine
+
+## Buttons, as shown in the tooltips and spoken by the screen readers
+
+button-clear = wissen
+button-invert = teken omkeren
+button-percent = procent
+button-divide = gedeeld door
+button-multiply = keer
+button-subtract = min
+button-add = plus
+button-equals = is gelijk aan
+button-decimal = komma
+
+## Settings
+
+setting-precision = Precisie
+setting-rounding = Afronding
+setting-notation = Notatie
+setting-input-length = Max. invoer
+setting-theme = Thema
+setting-locale = Getalnotatie
+setting-language = Taal
+setting-clicks = Toetsklikken
+setting-volume = Klikvolume
+setting-vibration = Trillen
+setting-on = aan
+setting-off = uit
+setting-auto = automatisch
+
+## Setting values
+
+language-name = Nederlands
+notation-auto = auto
+notation-fix = vast{ $decimals }
+notation-sci = wet{ $decimals }
+notation-eng = tech{ $decimals }
+rounding-half-even = half-even
+rounding-half-up = half-op
+rounding-half-down = half-neer
+rounding-toward-zero = naar-nul
+rounding-floor = omlaag
+rounding-ceiling = omhoog
+
+## Display indicators
+
+indicator-precision = { $notation } { $decimals }DEC { $rounding }
+
+## Panels
+
+tape-title = Rol
+tape-note = Notitie
+history-csv = CSV
+history-json = JSON
+history-import = Importeren
+
+## Messages
+
+error-calculation = Kan niet berekenen
+speech-voice-on = spraak aan
@@ -0,0 +1,62 @@
+# Portuguese translations.
+
+app-title = Calculadora Bevy
+
+## Buttons, as shown in the tooltips and spoken by the screen readers
+
+button-clear = limpar
+button-invert = inverter sinal
+button-percent = porcentagem
+button-divide = dividir
+button-multiply = multiplicar
+button-subtract = subtrair
+button-add = somar
+button-equals = igual
+button-decimal = vírgula
+
+## Settings
+
+setting-precision = Precisão
+setting-rounding = Arredondamento
+setting-notation = Notação
+setting-input-length = Entrada máx.
+setting-theme = Tema
+setting-locale = Formato numérico
+setting-language = Idioma
+setting-clicks = Cliques das teclas
+setting-volume = Volume dos cliques
+setting-vibration = Vibração
+setting-on = ligado
+setting-off = desligado
+setting-auto = automático
+
+## Setting values
+
+language-name = Português
+notation-auto = auto
+notation-fix = fix{ $decimals }
+notation-sci = cie{ $decimals }
+notation-eng = eng{ $decimals }
+rounding-half-even = meio-par
+rounding-half-up = meio-cima
+rounding-half-down = meio-baixo
+rounding-toward-zero = para-zero
+rounding-floor = piso
+rounding-ceiling = teto
+
+## Display indicators
+
+indicator-precision = { $notation } { $decimals }DEC { $rounding }
+
+## Panels
+
+tape-title = Bobina
+tape-note = Nota
+history-csv = CSV
+history-json = JSON
+history-import = Importar
+
+## Messages
+
+error-calculation = Não é possível calcular
+speech-voice-on = voz ativada
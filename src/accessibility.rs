@@ -0,0 +1,102 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::a11y::accesskit::{Live, Node as AccessNode, Role, Toggled};
+use bevy::a11y::{AccessibilityNode, AccessibilitySystems};
+use bevy::prelude::*;
+use bevy::text::EditableText;
+
+use crate::button::{ButtonAction, ButtonKind, ButtonVariant, CalcButton};
+use crate::localization::Localization;
+use crate::operation::OperationMetadata;
+use crate::viewport::DisplayViewport;
+
+/// Expose the calculator to screen readers through the accessibility tree: the buttons with their
+/// localized name, the operators as toggle buttons (the current one being pressed), and the
+/// display as a live region announcing the results.
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        // After the UI, which names the buttons after their label
+        app.add_systems(
+            PostUpdate,
+            (button_accessibility, display_accessibility).after(AccessibilitySystems::Update),
+        );
+    }
+}
+
+/// Give the calculator buttons their role, localized name and pressed state.
+#[allow(clippy::needless_pass_by_value)]
+fn button_accessibility(
+    mut commands: Commands,
+    mut buttons: Query<(Entity, &ButtonAction, Option<&mut AccessibilityNode>), With<CalcButton>>,
+    operation: Single<&OperationMetadata>,
+    localization: Res<Localization>,
+) {
+    let operator: Option<ButtonVariant> = operation
+        .operator()
+        .filter(|_| operation.is_under_operation())
+        .map(Into::into);
+
+    for (entity, ButtonAction(button), accessible) in &mut buttons {
+        let name = localization.button_name(*button);
+        // The operators are toggle buttons, pressed while their operation is pending
+        let toggled = match operator {
+            Some(operator) if operator == *button => Some(Toggled::True),
+            _ if ButtonKind::of(*button) == ButtonKind::Operator => Some(Toggled::False),
+            _ => None,
+        };
+
+        let Some(mut accessible) = accessible else {
+            let mut node = AccessNode::new(Role::Button);
+            node.set_label(name);
+            if let Some(toggled) = toggled {
+                node.set_toggled(toggled);
+            }
+            commands.entity(entity).insert(AccessibilityNode(node));
+            continue;
+        };
+
+        // Only touched when outdated, not to rebuild the accessibility tree every frame
+        if accessible.role() != Role::Button {
+            accessible.set_role(Role::Button);
+        }
+        if accessible.label() != Some(name.as_str()) {
+            accessible.set_label(name);
+        }
+        if accessible.toggled() != toggled {
+            match toggled {
+                Some(toggled) => accessible.set_toggled(toggled),
+                None => accessible.clear_toggled(),
+            }
+        }
+    }
+}
+
+/// Mark the display as a live region, its value being announced when changed.
+#[allow(clippy::needless_pass_by_value)]
+fn display_accessibility(
+    mut commands: Commands,
+    viewport: Single<(Entity, Option<&mut AccessibilityNode>), With<DisplayViewport>>,
+    display: Single<&EditableText, With<OperationMetadata>>,
+) {
+    let value = display.value().to_string();
+    let (entity, accessible) = viewport.into_inner();
+
+    let Some(mut accessible) = accessible else {
+        let mut node = AccessNode::new(Role::Status);
+        node.set_live(Live::Polite);
+        node.set_label(value);
+        commands.entity(entity).insert(AccessibilityNode(node));
+        return;
+    };
+
+    if accessible.label() != Some(value.as_str()) {
+        accessible.set_label(value);
+    }
+}
@@ -0,0 +1,133 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::text::{EditableText, TextEdit};
+
+use crate::button::ButtonVariant;
+use crate::engine::{self, Evaluation};
+use crate::expression;
+use crate::format::NumberFormat;
+use crate::operation::OperationMetadata;
+
+/// A calculator action, whatever its source (button click, keyboard, host page, ...).
+#[derive(Message, Clone, Debug)]
+pub enum CalcAction {
+    /// Press a calculator button
+    Press(ButtonVariant),
+    /// Evaluate a whole expression (e.g. `12 * (3 + 4)`) and show its result
+    Evaluate(String),
+}
+
+/// Sent every time a computed value is shown on the display.
+#[derive(Message, Clone, Debug)]
+pub struct CalcResult(pub Evaluation);
+
+/// Sent for every applied action, with the display value it was applied to (e.g. for the paper tape).
+#[derive(Message, Clone, Debug)]
+pub struct CalcApplied {
+    pub action: CalcAction,
+    /// Display value before the action
+    pub display: String,
+    /// Value computed by the action, if any
+    pub evaluation: Option<Evaluation>,
+}
+
+/// Sent for every action failing to apply (e.g. an invalid expression).
+#[derive(Message, Clone, Debug)]
+pub struct CalcFailed(pub CalcAction);
+
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<CalcAction>();
+        app.add_message::<CalcResult>();
+        app.add_message::<CalcApplied>();
+        app.add_message::<CalcFailed>();
+        app.init_resource::<NumberFormat>();
+        app.add_systems(Update, apply_calc_actions);
+    }
+}
+
+/// Apply all the pending calculator actions to the display and the operation state.
+///
+/// The actions are applied in order on the same frame, each one seeing the display value and the
+/// operand left by the previous one, the display being updated once at the end.
+#[allow(clippy::needless_pass_by_value)]
+pub fn apply_calc_actions(
+    mut actions: MessageReader<CalcAction>,
+    mut display: Single<(&mut EditableText, &mut OperationMetadata)>,
+    mut results: MessageWriter<CalcResult>,
+    mut applied: MessageWriter<CalcApplied>,
+    mut failures: MessageWriter<CalcFailed>,
+    format: Res<NumberFormat>,
+) {
+    let (editable, op_metadata) = &mut *display;
+    let current = editable.value().to_string();
+    let mut text = current.clone();
+
+    for action in actions.read() {
+        let display = text.clone();
+        match apply_action(action.clone(), &mut text, op_metadata, &format) {
+            Ok(evaluation) => {
+                if let Some(evaluation) = &evaluation {
+                    results.write(CalcResult(evaluation.clone()));
+                }
+                applied.write(CalcApplied {
+                    action: action.clone(),
+                    display,
+                    evaluation,
+                });
+            }
+            Err(err) => {
+                warn!("Failed to apply {action:?}: {err}");
+
+                failures.write(CalcFailed(action.clone()));
+            }
+        }
+    }
+
+    if text != current {
+        reset_editable(editable, &text);
+    }
+}
+
+/// Apply an action to the display text and the operation state.
+fn apply_action(
+    action: CalcAction,
+    text: &mut String,
+    op_metadata: &mut OperationMetadata,
+    format: &NumberFormat,
+) -> Result<Option<Evaluation>> {
+    let evaluation = match action {
+        CalcAction::Press(button) => {
+            engine::apply_button(button, text, op_metadata, format)?.apply(text, format)
+        }
+        CalcAction::Evaluate(expression) => {
+            let value = format.round(expression::evaluate(&expression)?);
+
+            info!("Calculating: {expression} = {value}");
+
+            op_metadata.reset();
+            *text = format.format(value);
+            op_metadata.set_shown(text.clone(), value);
+            Some(Evaluation { expression, value })
+        }
+    };
+
+    // Synced at once, for the next action to see it
+    op_metadata.sync_operand(text, format);
+
+    Ok(evaluation)
+}
+
+/// Replace the editable text and move the cursor to the end.
+pub fn reset_editable(editable: &mut EditableText, text: &str) {
+    editable.editor_mut().set_text(text);
+    editable.queue_edit(TextEdit::TextEnd(false));
+}
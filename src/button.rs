@@ -9,6 +9,8 @@ use bevy::prelude::*;
 use bevy::scene::SceneComponent;
 use bevy::ui::auto_directional_navigation::AutoDirectionalNavigation;
 
+use crate::theme::ThemeRole;
+
 /// Type definition to define the buttons
 pub type ButtonVariant = &'static str;
 
@@ -32,13 +34,93 @@ pub const SEVEN_BUTTON: ButtonVariant = "7";
 pub const EIGHT_BUTTON: ButtonVariant = "8";
 pub const NINE_BUTTON: ButtonVariant = "9";
 
-/// Type definition for define the buttons' states
-pub type ButtonState = Color;
+/// All the buttons, the frontends laying them out after a layout file (see
+/// [`crate::layout::ButtonLayout`]).
+pub const ALL_BUTTONS: [ButtonVariant; 19] = [
+    CLEAR_BUTTON,
+    INVERT_BUTTON,
+    POURCENT_BUTTON,
+    DIVIDE_BUTTON,
+    SEVEN_BUTTON,
+    EIGHT_BUTTON,
+    NINE_BUTTON,
+    MULTIPLY_BUTTON,
+    FOUR_BUTTON,
+    FIVE_BUTTON,
+    SIX_BUTTON,
+    SUB_BUTTON,
+    ONE_BUTTON,
+    TWO_BUTTON,
+    THREE_BUTTON,
+    ADD_BUTTON,
+    ZERO_BUTTON,
+    DOT_BUTTON,
+    EQUAL_BUTTON,
+];
+
+/// Find the button matching a label, e.g. the text of a button or a key typed by the user.
+pub fn from_label(label: &str) -> Option<ButtonVariant> {
+    ALL_BUTTONS
+        .iter()
+        .find(|button| **button == label)
+        .copied()
+        // The decimal button may be labelled with the decimal separator of the locale
+        .or_else(|| (label == ",").then_some(DOT_BUTTON))
+}
+
+/// Find the button matching a typed character.
+pub const fn from_char(c: char) -> Option<ButtonVariant> {
+    let button = match c {
+        '0' => ZERO_BUTTON,
+        '1' => ONE_BUTTON,
+        '2' => TWO_BUTTON,
+        '3' => THREE_BUTTON,
+        '4' => FOUR_BUTTON,
+        '5' => FIVE_BUTTON,
+        '6' => SIX_BUTTON,
+        '7' => SEVEN_BUTTON,
+        '8' => EIGHT_BUTTON,
+        '9' => NINE_BUTTON,
+        '.' | ',' => DOT_BUTTON,
+        '+' => ADD_BUTTON,
+        '-' => SUB_BUTTON,
+        '*' => MULTIPLY_BUTTON,
+        '/' => DIVIDE_BUTTON,
+        '%' => POURCENT_BUTTON,
+        '=' => EQUAL_BUTTON,
+        'c' | 'C' => CLEAR_BUTTON,
+        _ => return None,
+    };
 
-pub const NORMAL_BUTTON: ButtonState = Color::srgb(0.15, 0.15, 0.15);
-pub const HOVERED_BUTTON: ButtonState = Color::srgb(0.25, 0.25, 0.25);
-pub const PRESSED_BUTTON: ButtonState = Color::srgb(0.75, 0.75, 0.75);
-pub const FOCUSED_BUTTON: ButtonState = Color::srgb(0.2, 0.4, 0.8);
+    Some(button)
+}
+
+/// Visual category of a button, each one colored differently by the theme.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ButtonKind {
+    /// Digits and the decimal separator
+    #[default]
+    Digit,
+    /// Arithmetic operators
+    Operator,
+    /// Buttons transforming the display value (`+/-`, `%`)
+    Function,
+    Clear,
+    Equals,
+}
+
+impl ButtonKind {
+    /// Default kind of a button.
+    pub fn of(button: ButtonVariant) -> Self {
+        match button {
+            ADD_BUTTON | SUB_BUTTON | MULTIPLY_BUTTON | DIVIDE_BUTTON => Self::Operator,
+            INVERT_BUTTON | POURCENT_BUTTON => Self::Function,
+            CLEAR_BUTTON => Self::Clear,
+            EQUAL_BUTTON => Self::Equals,
+            _ => Self::Digit,
+        }
+    }
+}
 
 /// A calculator button. Spawning it creates the full button scene plus its
 /// `Text` child, so any system that queries for `CalcButton` can rely on
@@ -47,19 +129,29 @@ pub const FOCUSED_BUTTON: ButtonState = Color::srgb(0.2, 0.4, 0.8);
 #[scene(CalcButtonProps)]
 pub struct CalcButton;
 
+/// The button variant applied by a calculator button, independently of its (localized) label.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ButtonAction(pub ButtonVariant);
+
+/// The visual category of a calculator button.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ButtonCategory(pub ButtonKind);
+
 #[derive(Default, Clone, Copy)]
 pub struct CalcButtonProps {
     pub label: ButtonVariant,
+    pub kind: ButtonKind,
 }
 
 impl CalcButton {
     fn scene(props: CalcButtonProps) -> impl Scene {
         bsn! {
             Button
+            ButtonAction({props.label})
+            ButtonCategory({props.kind})
             AutoDirectionalNavigation::default()
+            // Filling its cells of the grid
             Node {
-                width: Val::Px(80.),
-                height: Val::Px(50.),
                 border: UiRect::all(Val::Px(2.)),
                 border_radius: BorderRadius::MAX,
                 margin: UiRect::all(Val::Percent(1.)),
@@ -67,11 +159,12 @@ impl CalcButton {
                 justify_content: JustifyContent::Center, // Horizontal
                 align_items: AlignItems::Center,         // Vertical
             }
-            BorderColor::all(Color::BLACK)
-            BackgroundColor(NORMAL_BUTTON)
+            BorderColor::default()
+            BackgroundColor::default()
             Children [(
                 Text({props.label})
-                TextColor::WHITE
+                TextColor::default()
+                ThemeRole::ButtonText
                 TextLayout::justify(Justify::Center)
                 TextShadow::default()
             )]
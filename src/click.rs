@@ -0,0 +1,247 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::audio::{AudioSource, Volume};
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+use crate::action::{CalcAction, CalcApplied, CalcFailed, apply_calc_actions};
+use crate::button::{ButtonVariant, DOT_BUTTON};
+
+/// Sample rate of the generated tones, in Hz.
+const SAMPLE_RATE: u32 = 44_100;
+/// Volumes selectable with `F6`, as a ratio of the full volume.
+const VOLUMES: [f32; 4] = [0.25, 0.5, 0.75, 1.];
+
+/// The tone played when a button is activated.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClickTone {
+    /// A digit or the decimal separator
+    Digit,
+    /// Any other button (operators, clear, etc.)
+    Operator,
+    /// An action failing to apply, or computing a non-finite value (e.g. a division by zero)
+    Error,
+}
+
+impl ClickTone {
+    pub fn of_button(button: ButtonVariant) -> Self {
+        if button == DOT_BUTTON || button.chars().all(|c| c.is_ascii_digit()) {
+            Self::Digit
+        } else {
+            Self::Operator
+        }
+    }
+
+    /// Frequency and duration (in seconds) of the tone.
+    const fn sound(self) -> (f32, f32) {
+        match self {
+            Self::Digit => (1200., 0.03),
+            Self::Operator => (800., 0.04),
+            Self::Error => (220., 0.15),
+        }
+    }
+
+    /// Duration of the vibration, in milliseconds (on mobile browsers).
+    #[cfg(target_arch = "wasm32")]
+    const fn vibration(self) -> u32 {
+        match self {
+            Self::Digit => 10,
+            Self::Operator => 20,
+            Self::Error => 60,
+        }
+    }
+}
+
+/// The key click settings, the clicks being muted by default, unlike the vibrations.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct ClickFeedback {
+    /// Volume of the clicks, from 0 to 1
+    pub volume: f32,
+    pub muted: bool,
+    /// Whether the device vibrates, independently of the sound (on mobile browsers)
+    pub vibration: bool,
+}
+
+impl Default for ClickFeedback {
+    fn default() -> Self {
+        Self {
+            volume: 0.5,
+            muted: true,
+            vibration: true,
+        }
+    }
+}
+
+impl ClickFeedback {
+    /// Switch to the next volume, wrapping around.
+    pub fn next_volume(&mut self) {
+        self.volume = VOLUMES
+            .into_iter()
+            .find(|volume| *volume > self.volume + f32::EPSILON)
+            .unwrap_or(VOLUMES[0]);
+    }
+}
+
+/// The generated sounds of the tones.
+#[derive(Resource, Clone, Debug)]
+struct ClickSounds {
+    digit: Handle<AudioSource>,
+    operator: Handle<AudioSource>,
+    error: Handle<AudioSource>,
+}
+
+impl ClickSounds {
+    fn get(&self, tone: ClickTone) -> Handle<AudioSource> {
+        match tone {
+            ClickTone::Digit => self.digit.clone(),
+            ClickTone::Operator => self.operator.clone(),
+            ClickTone::Error => self.error.clone(),
+        }
+    }
+}
+
+/// Play a click, with a distinct tone for the digits, the operators and the errors, when a button
+/// is activated (by a click or the keyboard), and vibrate on mobile browsers. Muted with `F5`, the
+/// volume is switched with `F6` (or from the settings panel, as the vibrations).
+pub struct ClickPlugin;
+
+impl Plugin for ClickPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ClickFeedback>();
+        app.add_systems(Startup, create_click_sounds);
+        app.add_systems(
+            Update,
+            (click_shortcuts, play_clicks.after(apply_calc_actions)),
+        );
+    }
+}
+
+/// A short sine tone fading out, as a 16-bit mono WAV file.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+fn tone_wav(frequency: f32, duration: f32) -> Vec<u8> {
+    let samples = (SAMPLE_RATE as f32 * duration) as u32;
+    let data_size = samples * 2;
+
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16_u32.to_le_bytes()); // Size of the format chunk
+    wav.extend_from_slice(&1_u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1_u16.to_le_bytes()); // Mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // Bytes per second
+    wav.extend_from_slice(&2_u16.to_le_bytes()); // Bytes per sample
+    wav.extend_from_slice(&16_u16.to_le_bytes()); // Bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+
+    for index in 0..samples {
+        let time = index as f32 / SAMPLE_RATE as f32;
+        let envelope = 1. - index as f32 / samples as f32;
+        let sample = (TAU * frequency * time).sin() * envelope * f32::from(i16::MAX);
+        wav.extend_from_slice(&(sample as i16).to_le_bytes());
+    }
+
+    wav
+}
+
+fn create_click_sounds(mut commands: Commands, mut sources: ResMut<Assets<AudioSource>>) {
+    let mut add = |tone: ClickTone| {
+        let (frequency, duration) = tone.sound();
+        sources.add(AudioSource {
+            bytes: tone_wav(frequency, duration).into(),
+        })
+    };
+
+    commands.insert_resource(ClickSounds {
+        digit: add(ClickTone::Digit),
+        operator: add(ClickTone::Operator),
+        error: add(ClickTone::Error),
+    });
+}
+
+/// Mute or unmute the clicks with `F5`, and switch to the next volume with `F6`.
+#[allow(clippy::needless_pass_by_value)]
+fn click_shortcuts(keys: Res<ButtonInput<KeyCode>>, mut feedback: ResMut<ClickFeedback>) {
+    if keys.just_pressed(KeyCode::F5) {
+        feedback.muted = !feedback.muted;
+
+        info!(
+            "Key clicks: {}",
+            if feedback.muted { "muted" } else { "on" }
+        );
+    } else if keys.just_pressed(KeyCode::F6) {
+        feedback.next_volume();
+
+        info!("Key clicks volume: {:.0}%", feedback.volume * 100.);
+    }
+}
+
+/// Play the clicks of the pressed buttons.
+#[allow(clippy::needless_pass_by_value)]
+fn play_clicks(
+    mut commands: Commands,
+    mut applied: MessageReader<CalcApplied>,
+    mut failures: MessageReader<CalcFailed>,
+    feedback: Res<ClickFeedback>,
+    sounds: Option<Res<ClickSounds>>,
+) {
+    let applied = applied.read().filter_map(|applied| {
+        let CalcAction::Press(button) = applied.action else {
+            return None;
+        };
+
+        let failed = applied
+            .evaluation
+            .as_ref()
+            .is_some_and(|evaluation| !evaluation.value.is_finite());
+        Some(if failed {
+            ClickTone::Error
+        } else {
+            ClickTone::of_button(button)
+        })
+    });
+    let failures = failures
+        .read()
+        .filter(|CalcFailed(action)| matches!(action, CalcAction::Press(_)))
+        .map(|_| ClickTone::Error);
+    let tones: Vec<ClickTone> = applied.chain(failures).collect();
+
+    // The vibrations don't depend on the sound being muted
+    #[cfg(target_arch = "wasm32")]
+    if feedback.vibration {
+        for tone in &tones {
+            vibrate(*tone);
+        }
+    }
+
+    let (Some(sounds), false) = (sounds, feedback.muted) else {
+        return;
+    };
+
+    for tone in tones {
+        commands.spawn((
+            AudioPlayer(sounds.get(tone)),
+            PlaybackSettings::DESPAWN.with_volume(Volume::Linear(feedback.volume)),
+            tone,
+        ));
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn vibrate(tone: ClickTone) {
+    // Ignored by the browsers without vibration support
+    if let Some(window) = web_sys::window() {
+        window.navigator().vibrate_with_duration(tone.vibration());
+    }
+}
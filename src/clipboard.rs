@@ -0,0 +1,206 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::text::EditableText;
+
+use crate::action::{self, CalcAction, apply_calc_actions};
+use crate::expression;
+use crate::format::NumberFormat;
+use crate::operation::OperationMetadata;
+
+/// Currency symbols stripped from the pasted text.
+const CURRENCY_SYMBOLS: [char; 10] = ['$', '€', '£', '¥', '₹', '₽', '₩', '¢', '₺', '₪'];
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    /// Text read from the clipboard by the browser, forwarded to the app on the next frame.
+    static PENDING_PASTES: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Text read from the clipboard, to paste on the display.
+#[derive(Message, Clone, Debug)]
+pub struct Paste(pub String);
+
+/// A sanitized pasted text.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pasted {
+    /// A number, replacing the display value
+    Number(f64),
+    /// A whole expression (e.g. `12 * (3 + 4)`), evaluated
+    Expression(String),
+}
+
+/// Strip the whitespaces, the digit group separators and the currency symbols from a pasted text,
+/// and tell whether it's a number or an expression. The decimal separator of the locale, and the
+/// mathematical symbols (`×`, `÷`, `−`) are replaced by their ASCII counterparts.
+pub fn sanitize(text: &str, format: &NumberFormat) -> Result<Pasted> {
+    let decimal_separator = format.decimal_separator();
+    let group_separator = format.locale.group_separator();
+
+    let text: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != group_separator && !CURRENCY_SYMBOLS.contains(c))
+        .map(|c| match c {
+            c if c == decimal_separator => '.',
+            '×' => '*',
+            '÷' => '/',
+            '−' => '-',
+            c => c,
+        })
+        .collect();
+
+    if text.is_empty() {
+        return Err("Nothing to paste".into());
+    }
+
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(Pasted::Number(value)),
+        _ => Ok(Pasted::Expression(text)),
+    }
+}
+
+/// Copy the display value with `Ctrl+C` (unformatted with `Ctrl+Shift+C`), and paste a number or an
+/// expression with `Ctrl+V`, through the OS clipboard or the Clipboard API of the browser.
+pub struct ClipboardPlugin;
+
+impl Plugin for ClipboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<Paste>();
+        app.add_systems(
+            Update,
+            (clipboard_shortcuts, apply_pastes.before(apply_calc_actions)).chain(),
+        );
+        #[cfg(target_arch = "wasm32")]
+        app.add_systems(Update, forward_pastes.before(apply_pastes));
+    }
+}
+
+/// Handle the copy and paste shortcuts (`Cmd` instead of `Ctrl` on macOS).
+#[allow(clippy::needless_pass_by_value)]
+fn clipboard_shortcuts(
+    keys: Res<ButtonInput<KeyCode>>,
+    display: Single<&EditableText, With<OperationMetadata>>,
+    format: Res<NumberFormat>,
+    mut pastes: MessageWriter<Paste>,
+) -> Result {
+    let modifier = keys.any_pressed([
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+        KeyCode::SuperLeft,
+        KeyCode::SuperRight,
+    ]);
+    if !modifier {
+        return Ok(());
+    }
+
+    if keys.just_pressed(KeyCode::KeyC) {
+        let display = display.value().to_string();
+        let text = if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            format.parse(&display)?.to_string()
+        } else {
+            display
+        };
+
+        debug!("Copying to the clipboard: {text}");
+
+        write_clipboard(&text)?;
+    } else if keys.just_pressed(KeyCode::KeyV) {
+        read_clipboard(&mut pastes)?;
+    }
+
+    Ok(())
+}
+
+/// Paste the clipboard text: a number replaces the display value, an expression is evaluated.
+#[allow(clippy::needless_pass_by_value)]
+fn apply_pastes(
+    mut pastes: MessageReader<Paste>,
+    mut display: Single<(&mut EditableText, &mut OperationMetadata)>,
+    mut actions: MessageWriter<CalcAction>,
+    format: Res<NumberFormat>,
+) {
+    let (editable, op_metadata) = &mut *display;
+    for Paste(text) in pastes.read() {
+        match sanitize(text, &format) {
+            Ok(Pasted::Number(value)) => {
+                debug!("Pasting the number: {value}");
+
+                // Kept at full precision, as a computed value
+                let shown = format.format(value);
+                action::reset_editable(editable, &shown);
+                op_metadata.set_shown(shown, value);
+            }
+            Ok(Pasted::Expression(expression)) => {
+                // Checked beforehand, as the JavaScript API does, an invalid expression isn't an app error
+                if let Err(err) = expression::evaluate(&expression) {
+                    warn!("Failed to paste '{text}': {err}");
+                    continue;
+                }
+
+                debug!("Pasting the expression: {expression}");
+
+                actions.write(CalcAction::Evaluate(expression));
+            }
+            Err(err) => warn!("Failed to paste '{text}': {err}"),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_clipboard(text: &str) -> Result {
+    arboard::Clipboard::new()?.set_text(text)?;
+
+    Ok(())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_clipboard(pastes: &mut MessageWriter<Paste>) -> Result {
+    pastes.write(Paste(arboard::Clipboard::new()?.get_text()?));
+
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn browser_clipboard() -> Result<web_sys::Clipboard> {
+    Ok(web_sys::window()
+        .ok_or("No browser window")?
+        .navigator()
+        .clipboard())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_clipboard(text: &str) -> Result {
+    // The promise is left alone, the browser reports the failures itself
+    let _ = browser_clipboard()?.write_text(text);
+
+    Ok(())
+}
+
+/// Read the clipboard asynchronously, the text is forwarded by `forward_pastes` once read.
+#[cfg(target_arch = "wasm32")]
+fn read_clipboard(_pastes: &mut MessageWriter<Paste>) -> Result {
+    use wasm_bindgen::prelude::*;
+
+    let on_read = Closure::once(|text: JsValue| {
+        if let Some(text) = text.as_string() {
+            PENDING_PASTES.with_borrow_mut(|pastes| pastes.push(text));
+        }
+    });
+    let _ = browser_clipboard()?.read_text().then(&on_read);
+    on_read.forget();
+
+    Ok(())
+}
+
+/// Forward the text read from the clipboard by the browser to the app.
+#[cfg(target_arch = "wasm32")]
+fn forward_pastes(mut pastes: MessageWriter<Paste>) {
+    PENDING_PASTES.with_borrow_mut(|pending_pastes| {
+        pastes.write_batch(pending_pastes.drain(..).map(Paste));
+    });
+}
@@ -0,0 +1,153 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+/// Default CSS selector of the canvas the calculator renders into.
+const DEFAULT_CANVAS: &str = "#bevy-canvas";
+/// Default theme name.
+const DEFAULT_THEME: &str = "dark";
+/// Default layout name.
+const DEFAULT_LAYOUT: &str = "basic";
+
+/// Get the value following a command line flag, e.g. `--record <path>`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn arg_value(flag: &str) -> Option<String> {
+    std::env::args().skip_while(|arg| arg != flag).nth(1)
+}
+
+/// Configuration of the calculator window.
+///
+/// On the Web, it's read from the page hosting the calculator, otherwise from the command line
+/// (see [`CalcConfig::load`]).
+#[derive(Resource, Clone, Debug)]
+pub struct CalcConfig {
+    /// CSS selector of the canvas to render into (Web only)
+    pub canvas: String,
+    pub width: u32,
+    pub height: u32,
+    /// Whether the canvas follows the size of its parent element (Web only)
+    pub fit_canvas_to_parent: bool,
+    /// Name of a built-in theme, or path of a custom theme file (not on the Web)
+    pub theme: String,
+    /// Name of the button layout, e.g. `basic` (see [`crate::layout::ButtonLayout`])
+    pub layout: String,
+    /// Whether the key presses and the results are spoken at startup (see [`crate::speech`])
+    pub speech: bool,
+}
+
+impl Default for CalcConfig {
+    fn default() -> Self {
+        Self {
+            canvas: DEFAULT_CANVAS.to_string(),
+            width: 330,
+            height: 315,
+            fit_canvas_to_parent: true,
+            theme: DEFAULT_THEME.to_string(),
+            layout: DEFAULT_LAYOUT.to_string(),
+            speech: false,
+        }
+    }
+}
+
+impl CalcConfig {
+    /// Load the configuration of this calculator instance.
+    ///
+    /// On the Web, the instance mounts on the canvas given by the `canvas` URL query parameter,
+    /// or else on the first `canvas[data-calculator]` element not already used by another instance,
+    /// or else on `#bevy-canvas`. The other settings come from the URL query parameters, overridden
+    /// by the `data-*` attributes of the canvas element:
+    ///
+    /// - `width` / `data-width`: width of the canvas, in pixels
+    /// - `height` / `data-height`: height of the canvas, in pixels
+    /// - `fit-to-parent` / `data-fit-to-parent`: `false` to keep the canvas size, rather than fitting it to
+    ///   its parent element
+    /// - `theme` / `data-theme`: name of the theme (`dark`, `light` or `high-contrast`)
+    /// - `layout` / `data-layout`: name of the button layout (`basic`)
+    /// - `speech` / `data-speech`: `true` to speak the key presses and the results
+    ///
+    /// Otherwise, the theme and the layout come from the `--theme <name or path>` and
+    /// `--layout <name>` command line flags, and the voice feedback is enabled by `--speech`.
+    pub fn load() -> Self {
+        std::cfg_select! {
+            target_arch = "wasm32" => Self::from_page().unwrap_or_default(),
+            _ => Self::from_args(),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn from_args() -> Self {
+        let default = Self::default();
+
+        Self {
+            theme: arg_value("--theme").unwrap_or(default.theme),
+            layout: arg_value("--layout").unwrap_or(default.layout),
+            speech: std::env::args().any(|arg| arg == "--speech"),
+            ..default
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn from_page() -> Option<Self> {
+        /// Attribute marking a canvas as used by a calculator instance
+        const MOUNTED_ATTRIBUTE: &str = "data-calculator-mounted";
+
+        let document = web_sys::window()?.document()?;
+        let search = document.location()?.search().ok()?;
+        let query = web_sys::UrlSearchParams::new_with_str(&search).ok()?;
+
+        let canvas = query.get("canvas").or_else(|| {
+            document
+                .query_selector(&format!(
+                    "canvas[data-calculator]:not([{MOUNTED_ATTRIBUTE}])"
+                ))
+                .ok()
+                .flatten()
+                .map(|element| {
+                    // Give the canvas an identifier, so it can be selected by the window
+                    if element.id().is_empty() {
+                        let index = document
+                            .query_selector_all(&format!("canvas[{MOUNTED_ATTRIBUTE}]"))
+                            .map_or(0, |elements| elements.length());
+                        element.set_id(&format!("bevy-canvas-{index}"));
+                    }
+                    format!("#{}", element.id())
+                })
+        });
+        let canvas = canvas.unwrap_or_else(|| DEFAULT_CANVAS.to_string());
+
+        let element = document.query_selector(&canvas).ok().flatten();
+        if let Some(element) = &element {
+            let _ = element.set_attribute(MOUNTED_ATTRIBUTE, "");
+        }
+
+        // Data attributes of the canvas take precedence over the URL query parameters
+        let setting = |name: &str| {
+            element
+                .as_ref()
+                .and_then(|element| element.get_attribute(&format!("data-{name}")))
+                .or_else(|| query.get(name))
+        };
+
+        let default = Self::default();
+
+        Some(Self {
+            width: setting("width")
+                .and_then(|width| width.parse().ok())
+                .unwrap_or(default.width),
+            height: setting("height")
+                .and_then(|height| height.parse().ok())
+                .unwrap_or(default.height),
+            fit_canvas_to_parent: setting("fit-to-parent")
+                .map_or(default.fit_canvas_to_parent, |fit| fit != "false"),
+            theme: setting("theme").unwrap_or(default.theme),
+            layout: setting("layout").unwrap_or(default.layout),
+            speech: setting("speech").is_some_and(|speech| speech != "false"),
+            canvas,
+        })
+    }
+}
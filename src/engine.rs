@@ -0,0 +1,230 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+use crate::button::{
+    ADD_BUTTON, CLEAR_BUTTON, DIVIDE_BUTTON, DOT_BUTTON, EIGHT_BUTTON, EQUAL_BUTTON, FIVE_BUTTON,
+    FOUR_BUTTON, INVERT_BUTTON, MULTIPLY_BUTTON, NINE_BUTTON, ONE_BUTTON, POURCENT_BUTTON,
+    SEVEN_BUTTON, SIX_BUTTON, SUB_BUTTON, THREE_BUTTON, TWO_BUTTON, ZERO_BUTTON,
+};
+use crate::format::NumberFormat;
+use crate::operation::{CalcOperator, OperationMetadata};
+
+/// How a button action changes the calculator display.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DisplayEdit {
+    /// Leave the display untouched.
+    Keep,
+    /// Insert the text at the end of the display.
+    Insert(String),
+    /// Replace the whole display with the text.
+    Replace(String),
+    /// Replace the whole display with a computed value.
+    Result(Evaluation),
+}
+
+impl DisplayEdit {
+    /// Apply the edit to the display text, returning the computed value shown, if any.
+    pub fn apply(self, display: &mut String, format: &NumberFormat) -> Option<Evaluation> {
+        match self {
+            Self::Keep => None,
+            Self::Insert(text) => {
+                display.push_str(&text);
+                None
+            }
+            Self::Replace(text) => {
+                *display = text;
+                None
+            }
+            Self::Result(evaluation) => {
+                *display = format.format(evaluation.value);
+                Some(evaluation)
+            }
+        }
+    }
+}
+
+/// A computed value, with the expression it comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub expression: String,
+    pub value: f64,
+}
+
+/// Apply a button action (digit, operator, etc.) to the operation state, given the current display value.
+///
+/// The returned edit must be applied to the display by the frontend (see [`DisplayEdit::apply`]),
+/// which is then responsible to sync the new display value into the operation (see
+/// [`OperationMetadata::sync_operand`]).
+pub fn apply_button(
+    button: &str,
+    current: &str,
+    op_metadata: &mut OperationMetadata,
+    format: &NumberFormat,
+) -> Result<DisplayEdit> {
+    let edit = match button {
+        // Digit buttons
+        ZERO_BUTTON | ONE_BUTTON | TWO_BUTTON | THREE_BUTTON | FOUR_BUTTON | FIVE_BUTTON
+        | SIX_BUTTON | SEVEN_BUTTON | EIGHT_BUTTON | NINE_BUTTON => {
+            // A computed value is formatted (e.g. `1,234` or `1.23e4`), so a new number is typed
+            if current == "0" || op_metadata.is_shown(current) {
+                DisplayEdit::Replace(button.to_string())
+            } else if format.accepts_digit(current) {
+                DisplayEdit::Insert(button.to_string())
+            } else {
+                DisplayEdit::Keep
+            }
+        }
+
+        // Operator buttons
+        CLEAR_BUTTON => {
+            op_metadata.reset();
+            DisplayEdit::Replace("0".to_string())
+        }
+        INVERT_BUTTON => {
+            let negated = (!current.is_empty() && current != "0").then(|| format!("-{current}"));
+            let new_text = current
+                .strip_prefix('-')
+                .map(str::to_string)
+                .or(negated)
+                .unwrap_or_else(|| current.to_string());
+            // Keep the full precision of a computed value
+            if let Some(value) = op_metadata.shown_value(current) {
+                op_metadata.set_shown(new_text.clone(), -value);
+            }
+            DisplayEdit::Replace(new_text)
+        }
+        POURCENT_BUTTON => {
+            let display_value = op_metadata.display_value(current, format)?;
+            let result_value = format.round(display_value / 100.0);
+
+            info!("Calculating: {display_value} % = {result_value}");
+
+            op_metadata.reset();
+            DisplayEdit::Result(Evaluation {
+                expression: format!("{display_value} %"),
+                value: result_value,
+            })
+        }
+        ADD_BUTTON | SUB_BUTTON | MULTIPLY_BUTTON | DIVIDE_BUTTON => {
+            // Handle the case the user clicks on an operator before clicking on number buttons
+            if op_metadata.left_operand().is_none() {
+                op_metadata.set_left_operand(op_metadata.display_value(current, format)?);
+            }
+
+            let operator = match button {
+                ADD_BUTTON => CalcOperator::Add,
+                SUB_BUTTON => CalcOperator::Sub,
+                MULTIPLY_BUTTON => CalcOperator::Mul,
+                DIVIDE_BUTTON => CalcOperator::Div,
+                _ => unreachable!(),
+            };
+            op_metadata.set_operator(operator);
+
+            // Clear the display for the next operand. The frontend will sync the
+            // cleared value into the appropriate operand.
+            DisplayEdit::Replace("0".to_string())
+        }
+        DOT_BUTTON if op_metadata.is_shown(current) => {
+            DisplayEdit::Replace(format!("0{}", format.decimal_separator()))
+        }
+        DOT_BUTTON if !current.contains(format.decimal_separator()) => {
+            DisplayEdit::Insert(format.decimal_separator().to_string())
+        }
+        EQUAL_BUTTON if op_metadata.is_under_operation() => {
+            let result_value = format.round(op_metadata.calculate()?);
+            let expression = op_metadata.to_string();
+            op_metadata.reset();
+            DisplayEdit::Result(Evaluation {
+                expression,
+                value: result_value,
+            })
+        }
+
+        _ => DisplayEdit::Keep,
+    };
+
+    // The computed values are rounded for the display, but kept at full precision as operands
+    if let DisplayEdit::Result(evaluation) = &edit {
+        op_metadata.set_shown(format.format(evaluation.value), evaluation.value);
+    }
+
+    Ok(edit)
+}
+
+/// A calculator owning its display value, for frontends which don't rely on an `EditableText`.
+#[cfg(any(test, feature = "tui"))]
+#[derive(Clone)]
+pub struct Calculator {
+    display: String,
+    op_metadata: OperationMetadata,
+    format: NumberFormat,
+}
+
+#[cfg(any(test, feature = "tui"))]
+impl Default for Calculator {
+    fn default() -> Self {
+        Self {
+            display: "0".to_string(),
+            op_metadata: OperationMetadata::default(),
+            format: NumberFormat::default(),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "tui"))]
+impl Calculator {
+    pub fn new(format: NumberFormat) -> Self {
+        Self {
+            format,
+            ..Self::default()
+        }
+    }
+
+    /// Press a button, returning the computed value if the button shows one.
+    pub fn press(&mut self, button: &str) -> Result<Option<Evaluation>> {
+        let edit = apply_button(button, &self.display, &mut self.op_metadata, &self.format)?;
+        if edit == DisplayEdit::Keep {
+            return Ok(None);
+        }
+
+        let evaluation = edit.apply(&mut self.display, &self.format);
+        self.op_metadata.sync_operand(&self.display, &self.format);
+
+        Ok(evaluation)
+    }
+
+    /// Remove the last character of the display, falling back to `0` once empty.
+    #[cfg(feature = "tui")]
+    pub fn backspace(&mut self) {
+        self.display.pop();
+        if self.display.is_empty() || self.display == "-" {
+            self.display = "0".to_string();
+        }
+
+        self.op_metadata.sync_operand(&self.display, &self.format);
+    }
+
+    pub fn display(&self) -> &str {
+        &self.display
+    }
+
+    pub const fn operation(&self) -> &OperationMetadata {
+        &self.op_metadata
+    }
+
+    #[cfg(feature = "tui")]
+    pub const fn format(&self) -> &NumberFormat {
+        &self.format
+    }
+
+    #[cfg(feature = "tui")]
+    pub const fn format_mut(&mut self) -> &mut NumberFormat {
+        &mut self.format
+    }
+}
@@ -0,0 +1,242 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::window::FileDragAndDrop;
+
+use crate::theme::Theme;
+
+#[cfg(target_arch = "wasm32")]
+thread_local! {
+    /// Files picked in the browser and read, forwarded to the app on the next frame.
+    static PENDING_FILES: std::cell::RefCell<Vec<OpenedFile>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// A file opened to be imported: dropped on the window on desktop, picked on the Web.
+#[derive(Message, Clone, Debug)]
+pub struct OpenedFile {
+    pub name: String,
+    pub contents: String,
+}
+
+impl OpenedFile {
+    /// Extension of the file name, lowercased.
+    pub fn extension(&self) -> Option<String> {
+        std::path::Path::new(&self.name)
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+    }
+}
+
+/// A button of a panel exporting or importing files (e.g. `CSV`).
+#[derive(Component, Default, Clone)]
+pub struct FileButton;
+
+/// Read the files to import, and highlight the hovered file buttons.
+pub struct ExportPlugin;
+
+impl Plugin for ExportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<OpenedFile>();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Already added by the window plugin, except in the headless app
+            app.add_message::<FileDragAndDrop>();
+            app.add_systems(Update, read_dropped_files);
+        }
+        #[cfg(target_arch = "wasm32")]
+        app.add_systems(Update, forward_picked_files);
+        app.add_systems(Update, file_buttons_state);
+    }
+}
+
+/// Quote a CSV field when it contains a separator, a quote or a line break.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Split a CSV file into its records and their fields, unquoting them. The quoted fields may span
+/// several lines, and the blank lines are skipped.
+pub fn csv_records(contents: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let field = fields.last_mut().expect("There's always a field");
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            '\r' if !quoted => {}
+            '\n' if !quoted => records.push(std::mem::replace(&mut fields, vec![String::new()])),
+            c => field.push(c),
+        }
+    }
+    records.push(fields);
+
+    records.retain(|fields| !matches!(fields.as_slice(), [field] if field.trim().is_empty()));
+    records
+}
+
+/// Save an exported file: in the working directory on desktop, numbered rather than overwriting an
+/// existing file (e.g. `tape-1.csv`), downloaded by the browser on the Web.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(file_name: &str, _mime_type: &str, contents: &str) -> Result {
+    use std::io::Write;
+
+    let path = std::path::Path::new(file_name);
+    let (stem, extension) = (
+        path.file_stem().unwrap_or_default().to_string_lossy(),
+        path.extension().unwrap_or_default().to_string_lossy(),
+    );
+
+    for number in 0..1000 {
+        let path = if number == 0 {
+            path.to_path_buf()
+        } else {
+            path.with_file_name(format!("{stem}-{number}.{extension}"))
+        };
+
+        match std::fs::File::create_new(&path) {
+            Ok(mut file) => {
+                file.write_all(contents.as_bytes())?;
+
+                let path = std::path::absolute(&path)?;
+                info!("Exported to: {}", path.display());
+
+                return Ok(());
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Err(format!("No free file name to export to: {file_name}").into())
+}
+
+/// Save an exported file: in the working directory on desktop, downloaded by the browser on the Web.
+#[cfg(target_arch = "wasm32")]
+pub fn save(file_name: &str, mime_type: &str, contents: &str) -> Result {
+    use wasm_bindgen::JsCast;
+
+    let link = document()?
+        .create_element("a")
+        .map_err(|_| "Failed to create the download link")?;
+    let href = format!(
+        "data:{mime_type};charset=utf-8,{}",
+        String::from(js_sys::encode_uri_component(contents))
+    );
+    link.set_attribute("href", &href)
+        .and_then(|()| link.set_attribute("download", file_name))
+        .map_err(|_| "Failed to create the download link")?;
+    link.dyn_into::<web_sys::HtmlElement>()
+        .map_err(|_| "Failed to create the download link")?
+        .click();
+
+    info!("Exported to: {file_name}");
+
+    Ok(())
+}
+
+/// Let the user pick a file in the browser, the file is forwarded by `forward_picked_files` once read.
+#[cfg(target_arch = "wasm32")]
+pub fn open(accept: &str) -> Result {
+    use wasm_bindgen::prelude::*;
+
+    let input = document()?
+        .create_element("input")
+        .ok()
+        .and_then(|input| input.dyn_into::<web_sys::HtmlInputElement>().ok())
+        .ok_or("Failed to create the file picker")?;
+    input.set_type("file");
+    input.set_accept(accept);
+
+    let picker = input.clone();
+    let on_change = Closure::once(move || {
+        let Some(file) = picker.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        let name = file.name();
+        let on_read = Closure::once(move |contents: JsValue| {
+            if let Some(contents) = contents.as_string() {
+                PENDING_FILES.with_borrow_mut(|files| files.push(OpenedFile { name, contents }));
+            }
+        });
+        let _ = file.text().then(&on_read);
+        on_read.forget();
+    });
+    input.set_onchange(Some(on_change.as_ref().unchecked_ref()));
+    on_change.forget();
+    input.click();
+
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn document() -> Result<web_sys::Document> {
+    Ok(web_sys::window()
+        .ok_or("No browser window")?
+        .document()
+        .ok_or("No document")?)
+}
+
+/// Read the files dropped on the window.
+#[cfg(not(target_arch = "wasm32"))]
+fn read_dropped_files(
+    mut drops: MessageReader<FileDragAndDrop>,
+    mut files: MessageWriter<OpenedFile>,
+) {
+    for drop in drops.read() {
+        let FileDragAndDrop::DroppedFile { path_buf, .. } = drop else {
+            continue;
+        };
+
+        match std::fs::read_to_string(path_buf) {
+            Ok(contents) => {
+                files.write(OpenedFile {
+                    name: path_buf.to_string_lossy().to_string(),
+                    contents,
+                });
+            }
+            Err(err) => warn!("Failed to read '{}': {err}", path_buf.display()),
+        }
+    }
+}
+
+/// Forward the files picked in the browser to the app.
+#[cfg(target_arch = "wasm32")]
+fn forward_picked_files(mut files: MessageWriter<OpenedFile>) {
+    PENDING_FILES.with_borrow_mut(|pending_files| {
+        files.write_batch(pending_files.drain(..));
+    });
+}
+
+/// Highlight the hovered file buttons.
+#[allow(clippy::needless_pass_by_value)]
+fn file_buttons_state(
+    mut buttons: Query<(&Interaction, &mut BackgroundColor, &mut BorderColor), With<FileButton>>,
+    theme: Res<Theme>,
+) {
+    for (interaction, mut bg_color, mut border_color) in &mut buttons {
+        let color = match interaction {
+            Interaction::None => theme.function.normal,
+            Interaction::Hovered | Interaction::Pressed => theme.function.hovered,
+        };
+        bg_color.set_if_neq(BackgroundColor(color));
+        border_color.set_if_neq(BorderColor::all(theme.border));
+    }
+}
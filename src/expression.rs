@@ -0,0 +1,128 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+/// Evaluate an arithmetic expression such as `12 * (3 + 4) - 50%`.
+///
+/// Supports `+`, `-`, `*`, `/` with the usual precedence, unary signs, parentheses and a
+/// postfix `%` dividing its operand by 100. `×` and `÷` are accepted as operator aliases.
+pub fn evaluate(expression: &str) -> Result<f64> {
+    let mut parser = Parser {
+        chars: expression.char_indices().peekable(),
+    };
+
+    let value = parser.expression()?;
+    parser.skip_whitespaces();
+
+    if let Some((position, c)) = parser.chars.next() {
+        return Err(format!("Unexpected character '{c}' at position {position}").into());
+    }
+
+    Ok(value)
+}
+
+/// Recursive descent parser, evaluating the expression while it's parsed.
+struct Parser<'a> {
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    /// expression = term (('+' | '-') term)*
+    fn expression(&mut self) -> Result<f64> {
+        let mut value = self.term()?;
+
+        while let Some(operator) = self.next_if_any(&['+', '-']) {
+            let rhs = self.term()?;
+            value = if operator == '+' {
+                value + rhs
+            } else {
+                value - rhs
+            };
+        }
+
+        Ok(value)
+    }
+
+    /// term = factor (('*' | '/') factor)*
+    fn term(&mut self) -> Result<f64> {
+        let mut value = self.factor()?;
+
+        while let Some(operator) = self.next_if_any(&['*', '/', '×', '÷']) {
+            let rhs = self.factor()?;
+            value = if matches!(operator, '*' | '×') {
+                value * rhs
+            } else {
+                value / rhs
+            };
+        }
+
+        Ok(value)
+    }
+
+    /// factor = ('+' | '-') factor | primary '%'*
+    fn factor(&mut self) -> Result<f64> {
+        if let Some(sign) = self.next_if_any(&['+', '-']) {
+            let value = self.factor()?;
+            return Ok(if sign == '-' { -value } else { value });
+        }
+
+        let mut value = self.primary()?;
+        while self.next_if_any(&['%']).is_some() {
+            value /= 100.0;
+        }
+
+        Ok(value)
+    }
+
+    /// primary = number | '(' expression ')'
+    fn primary(&mut self) -> Result<f64> {
+        if self.next_if_any(&['(']).is_some() {
+            let value = self.expression()?;
+            if self.next_if_any(&[')']).is_none() {
+                return Err("Missing closing parenthesis".into());
+            }
+            return Ok(value);
+        }
+
+        self.number()
+    }
+
+    fn number(&mut self) -> Result<f64> {
+        self.skip_whitespaces();
+
+        let mut literal = String::new();
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_digit() || *c == '.') {
+            literal.push(c);
+        }
+
+        if literal.is_empty() {
+            return match self.chars.peek() {
+                Some((position, c)) => {
+                    Err(format!("Unexpected character '{c}' at position {position}").into())
+                }
+                None => Err("Unexpected end of expression".into()),
+            };
+        }
+
+        Ok(literal.parse::<f64>()?)
+    }
+
+    /// Consume the next non-whitespace character if it's one of the expected ones.
+    fn next_if_any(&mut self, expected: &[char]) -> Option<char> {
+        self.skip_whitespaces();
+        self.chars
+            .next_if(|(_, c)| expected.contains(c))
+            .map(|(_, c)| c)
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+}
@@ -0,0 +1,100 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+use crate::action::{CalcAction, CalcApplied, CalcFailed, CalcResult, apply_calc_actions};
+use crate::button::CLEAR_BUTTON;
+use crate::format::NumberFormat;
+use crate::localization::Localization;
+use crate::operation::OperationMetadata;
+use crate::theme::ThemeRole;
+
+/// Line above the display, showing the pending operation (e.g. `12 ×`), then the evaluated
+/// expression once computed (e.g. `12 × 7 =`), or an error message if it fails.
+#[derive(Component, Default, Clone)]
+pub struct ExpressionLine;
+
+pub struct ExpressionLinePlugin;
+
+impl Plugin for ExpressionLinePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, update_expression_line.after(apply_calc_actions));
+    }
+}
+
+/// The expression line, to be placed above the display.
+pub fn expression_line() -> impl Scene {
+    bsn! {
+        Node {
+            width: Val::Percent(80.),
+            min_height: Val::Px(14.),
+        }
+        ExpressionLine
+        Text::default()
+        TextFont::from_font_size(11.)
+        TextColor::default()
+        ThemeRole::SecondaryText
+        TextLayout::new(Justify::Center, LineBreak::NoWrap)
+    }
+}
+
+/// Show the typed operators with the mathematical symbols.
+pub fn prettify(expression: &str) -> String {
+    expression.replace('*', "×").replace('/', "÷")
+}
+
+/// Follow the ongoing operation and the computed values on the expression line.
+#[allow(clippy::needless_pass_by_value)]
+fn update_expression_line(
+    mut applied: MessageReader<CalcApplied>,
+    mut results: MessageReader<CalcResult>,
+    mut failures: MessageReader<CalcFailed>,
+    operation: Single<&OperationMetadata>,
+    mut line: Single<&mut Text, With<ExpressionLine>>,
+    mut previous_operation: Local<OperationMetadata>,
+    format: Res<NumberFormat>,
+    localization: Res<Localization>,
+) {
+    let operation = *operation;
+    // Only the applied clears, not the failed ones
+    let cleared = applied
+        .read()
+        .filter(
+            |applied| matches!(applied.action, CalcAction::Press(button) if button == CLEAR_BUTTON),
+        )
+        .count()
+        > 0;
+
+    let failed = failures.read().count() > 0;
+
+    let text = if failed {
+        Some(localization.text("error-calculation"))
+    } else if let Some(CalcResult(evaluation)) = results.read().last() {
+        // The operation is reset once computed, describe it as it was on the previous frame
+        let expression = if evaluation.expression == previous_operation.to_string() {
+            previous_operation.describe(&format, true)
+        } else {
+            prettify(&evaluation.expression)
+        };
+        Some(format!("{expression} ="))
+    } else if operation.is_under_operation() {
+        Some(operation.describe(&format, false))
+    } else if cleared || previous_operation.is_under_operation() {
+        Some(String::new())
+    } else {
+        // Keep the last computed expression while the next operand is typed
+        None
+    };
+
+    if let Some(text) = text
+        && line.0 != text
+    {
+        line.0 = text;
+    }
+    previous_operation.clone_from(operation);
+}
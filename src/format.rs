@@ -0,0 +1,379 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::locale::Locale;
+
+/// Exponent range displayed without scientific notation, in `Auto` notation.
+const AUTO_MIN_EXPONENT: i32 = -5;
+
+/// Notation used to display the computed values.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Notation {
+    /// Plain decimal, switching to scientific notation for very large or small values
+    #[default]
+    Auto,
+    /// Fixed number of decimals (FIX n)
+    Fix(u8),
+    /// Scientific notation with a fixed number of decimals (SCI n)
+    Sci(u8),
+    /// Engineering notation, exponent multiple of 3, with a fixed number of decimals (ENG n)
+    Eng(u8),
+}
+
+impl fmt::Display for Notation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Fix(decimals) => write!(f, "fix{decimals}"),
+            Self::Sci(decimals) => write!(f, "sci{decimals}"),
+            Self::Eng(decimals) => write!(f, "eng{decimals}"),
+        }
+    }
+}
+
+impl FromStr for Notation {
+    type Err = BevyError;
+
+    /// Parse a notation such as `auto`, `fix2`, `sci4` or `eng3`.
+    fn from_str(notation: &str) -> Result<Self> {
+        let notation = notation.to_ascii_lowercase();
+        if notation == "auto" {
+            return Ok(Self::Auto);
+        }
+
+        let kind_end = notation
+            .char_indices()
+            .nth(3)
+            .map_or(notation.len(), |(index, _)| index);
+        let (kind, decimals) = notation.split_at(kind_end);
+        let decimals = decimals.parse::<u8>()?;
+        match kind {
+            "fix" => Ok(Self::Fix(decimals)),
+            "sci" => Ok(Self::Sci(decimals)),
+            "eng" => Ok(Self::Eng(decimals)),
+            _ => Err(format!("Unknown notation: '{notation}'").into()),
+        }
+    }
+}
+
+impl Notation {
+    /// Next notation, keeping the number of decimals (settings panel).
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Auto => Self::Fix(2),
+            Self::Fix(decimals) => Self::Sci(decimals),
+            Self::Sci(decimals) => Self::Eng(decimals),
+            Self::Eng(_) => Self::Auto,
+        }
+    }
+}
+
+/// Rounding mode applied to the computed values, once rounded to the precision.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round half to the nearest even digit (banker's rounding)
+    #[default]
+    HalfEven,
+    /// Round half away from zero
+    HalfUp,
+    /// Round half toward zero
+    HalfDown,
+    /// Round toward zero, i.e. truncate the extra decimals
+    TowardZero,
+    /// Round toward negative infinity
+    Floor,
+    /// Round toward positive infinity
+    Ceiling,
+}
+
+impl RoundingMode {
+    pub const ALL: [Self; 6] = [
+        Self::HalfEven,
+        Self::HalfUp,
+        Self::HalfDown,
+        Self::TowardZero,
+        Self::Floor,
+        Self::Ceiling,
+    ];
+
+    /// Next rounding mode (settings panel).
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|mode| *mode == self)
+            .unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Round a value to a number of decimals.
+    pub fn round(self, value: f64, decimals: u8) -> f64 {
+        let scale = 10_f64.powi(i32::from(decimals));
+        let scaled = value * scale;
+        // Values beyond the f64 integer precision have no decimals to round
+        if !scaled.is_finite() || scaled.abs() >= 2_f64.powi(52) {
+            return value;
+        }
+
+        // Absorb the binary representation error, e.g. 1.005 * 100 = 100.49999999999999
+        let half = (scaled * 2.).round() / 2.;
+        let scaled = if (scaled - half).abs() <= scaled.abs() * 1e-12 {
+            half
+        } else {
+            scaled
+        };
+
+        let rounded = match self {
+            Self::HalfEven => scaled.round_ties_even(),
+            Self::HalfUp => scaled.round(),
+            Self::HalfDown if scaled.fract().abs() == 0.5 => scaled.trunc(),
+            Self::HalfDown => scaled.round(),
+            Self::TowardZero => scaled.trunc(),
+            Self::Floor => scaled.floor(),
+            Self::Ceiling => scaled.ceil(),
+        };
+
+        rounded / scale
+    }
+}
+
+impl fmt::Display for RoundingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self {
+            Self::HalfEven => "half-even",
+            Self::HalfUp => "half-up",
+            Self::HalfDown => "half-down",
+            Self::TowardZero => "toward-zero",
+            Self::Floor => "floor",
+            Self::Ceiling => "ceiling",
+        };
+
+        write!(f, "{mode}")
+    }
+}
+
+impl FromStr for RoundingMode {
+    type Err = BevyError;
+
+    /// Parse a rounding mode such as `half-even` or `floor`, `truncate` being an alias of
+    /// `toward-zero`.
+    fn from_str(mode: &str) -> Result<Self> {
+        let mode = mode.to_ascii_lowercase();
+        if mode == "truncate" {
+            return Ok(Self::TowardZero);
+        }
+
+        Self::ALL
+            .into_iter()
+            .find(|candidate| candidate.to_string() == mode)
+            .ok_or_else(|| format!("Unknown rounding mode: '{mode}'").into())
+    }
+}
+
+/// How the computed values are written on the display.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct NumberFormat {
+    pub notation: Notation,
+    /// Whether the integer digits are grouped by thousands
+    pub grouping: bool,
+    /// Locale defining the decimal and digit group separators
+    pub locale: Locale,
+    /// Number of decimals the computed values are rounded to, if any
+    pub precision: Option<u8>,
+    pub rounding: RoundingMode,
+    /// Maximum number of significant digits, in `Auto` notation
+    pub significant_digits: u8,
+    /// Maximum number of characters fitting on the display
+    pub max_width: usize,
+    /// Maximum number of digits typed on the display
+    pub max_input_length: usize,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            notation: Notation::Auto,
+            grouping: true,
+            locale: Locale::default(),
+            precision: None,
+            rounding: RoundingMode::default(),
+            significant_digits: 12,
+            max_width: 16,
+            max_input_length: 16,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Format a value for the display, falling back to fewer digits if it doesn't fit.
+    pub fn format(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+
+        let text = match self.notation {
+            Notation::Auto => self.auto(value, self.significant_digits),
+            Notation::Fix(decimals) => self.group(&format!("{value:.*}", usize::from(decimals))),
+            Notation::Sci(decimals) => {
+                self.localize(&format!("{value:.*e}", usize::from(decimals)))
+            }
+            Notation::Eng(decimals) => self.localize(&engineering(value, decimals)),
+        };
+        if text.chars().count() <= self.max_width {
+            return text;
+        }
+
+        // Drop significant digits until it fits
+        (1..self.significant_digits)
+            .rev()
+            .map(|digits| self.auto(value, digits))
+            .find(|text| text.chars().count() <= self.max_width)
+            .unwrap_or(text)
+    }
+
+    /// Round a computed value to the precision, if any.
+    pub fn round(&self, value: f64) -> f64 {
+        self.precision
+            .map_or(value, |decimals| self.rounding.round(value, decimals))
+    }
+
+    /// Whether another digit can be typed after the given display text.
+    pub fn accepts_digit(&self, text: &str) -> bool {
+        text.chars().filter(char::is_ascii_digit).count() < self.max_input_length
+    }
+
+    /// Drop the digits typed beyond the maximum input length, if any.
+    pub fn truncate_input(&self, text: &str) -> Option<String> {
+        let (end, _) = text
+            .char_indices()
+            .filter(|(_, c)| c.is_ascii_digit())
+            .nth(self.max_input_length)?;
+
+        Some(text[..end].to_string())
+    }
+
+    /// Normalize a typed text, if needed: the digit group separator is rejected (e.g. `,` in
+    /// English, which would be ambiguous), and the other separator is read as the decimal one.
+    pub fn normalize_input(&self, text: &str) -> Option<String> {
+        let decimal_separator = self.locale.decimal_separator();
+        let group_separator = self.locale.group_separator();
+
+        let normalized: String = text
+            .chars()
+            .filter(|c| *c != group_separator)
+            .map(|c| {
+                if matches!(c, '.' | ',') {
+                    decimal_separator
+                } else {
+                    c
+                }
+            })
+            .collect();
+
+        (normalized != text).then_some(normalized)
+    }
+
+    /// Parse a value from the display, ignoring the digit group separators.
+    pub fn parse(&self, text: &str) -> Result<f64> {
+        let group_separator = self.locale.group_separator();
+
+        // Once the group separators dropped, both separators are read as the decimal one
+        let text: String = text
+            .chars()
+            .filter(|c| *c != group_separator && !c.is_whitespace())
+            .map(|c| if matches!(c, '.' | ',') { '.' } else { c })
+            .collect();
+
+        Ok(text.parse::<f64>()?)
+    }
+
+    pub const fn decimal_separator(&self) -> char {
+        self.locale.decimal_separator()
+    }
+
+    fn auto(&self, value: f64, significant_digits: u8) -> String {
+        if value == 0. {
+            return "0".to_string();
+        }
+
+        // Round to the significant digits, through the scientific notation
+        let precision = usize::from(significant_digits.max(1) - 1);
+        let scientific = format!("{value:.precision$e}");
+        let (mantissa, exponent) = scientific
+            .split_once('e')
+            .expect("Scientific notation has an exponent");
+        let exponent: i32 = exponent.parse().expect("Valid exponent");
+
+        if (AUTO_MIN_EXPONENT..i32::from(significant_digits)).contains(&exponent) {
+            // The shortest representation of the rounded value drops the trailing zeros
+            let rounded: f64 = scientific.parse().expect("Valid scientific notation");
+            self.group(&rounded.to_string())
+        } else {
+            let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+            self.localize(&format!("{mantissa}e{exponent}"))
+        }
+    }
+
+    /// Replace the decimal point with the decimal separator of the locale.
+    fn localize(&self, text: &str) -> String {
+        text.replace('.', &self.locale.decimal_separator().to_string())
+    }
+
+    /// Group the integer digits of a plain decimal number by thousands, and localize its decimal separator.
+    fn group(&self, text: &str) -> String {
+        if !self.grouping {
+            return self.localize(text);
+        }
+
+        let (sign, unsigned) = text
+            .strip_prefix('-')
+            .map_or(("", text), |unsigned| ("-", unsigned));
+        let (integer, decimals) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+        let mut grouped = String::with_capacity(text.len() + integer.len() / 3);
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i).is_multiple_of(3) {
+                grouped.push(self.locale.group_separator());
+            }
+            grouped.push(digit);
+        }
+        if !decimals.is_empty() {
+            grouped.push(self.locale.decimal_separator());
+            grouped.push_str(decimals);
+        }
+
+        format!("{sign}{grouped}")
+    }
+}
+
+/// Format a value with an exponent multiple of 3.
+fn engineering(value: f64, decimals: u8) -> String {
+    let decimals = usize::from(decimals);
+    if value == 0. {
+        return format!("{value:.decimals$}e0");
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let mut exponent = value.abs().log10().floor() as i32;
+    exponent -= exponent.rem_euclid(3);
+
+    let mut mantissa = value / 10_f64.powi(exponent);
+    // Rounding may carry the mantissa to the next multiple of 1000
+    if format!("{:.decimals$}", mantissa.abs())
+        .parse::<f64>()
+        .unwrap_or_default()
+        >= 1000.
+    {
+        exponent += 3;
+        mantissa /= 1000.;
+    }
+
+    format!("{mantissa:.decimals$}e{exponent}")
+}
@@ -0,0 +1,418 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::action::{CalcResult, apply_calc_actions};
+use crate::export::{self, FileButton, OpenedFile, csv_field, csv_records};
+use crate::expression_line::prettify;
+use crate::format::NumberFormat;
+use crate::layout::ButtonLayout;
+use crate::localization::Localized;
+use crate::theme::ThemeRole;
+
+/// Maximum number of computed values kept in the history, the oldest ones are dropped beyond.
+const MAX_ENTRIES: usize = 100;
+/// First line of an exported CSV file.
+const CSV_HEADER: &str = "expression,result,timestamp,mode";
+
+/// A computed value of the history.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub expression: String,
+    #[serde(rename = "result", with = "json_value")]
+    pub value: f64,
+    /// When it was computed, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Name of the button layout it was computed with (e.g. `basic`, `mini`)
+    pub mode: String,
+}
+
+/// The computed values in JSON, the non-finite ones (e.g. `1 / 0`) being written as strings (`inf`,
+/// `-inf`, `NaN`), as JSON numbers can't represent them.
+mod json_value {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(f64),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.is_finite() {
+            Value::Number(*value)
+        } else {
+            Value::Text(value.to_string())
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::Number(value) => Ok(value),
+            Value::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// A file format the history can be exported to, and imported from.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl HistoryFormat {
+    pub const fn file_name(self) -> &'static str {
+        match self {
+            Self::Csv => "history.csv",
+            Self::Json => "history.json",
+        }
+    }
+
+    pub const fn mime_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Json => "application/json",
+        }
+    }
+
+    /// The format of a file, after its extension.
+    pub fn of_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// The computed values, oldest first.
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Add a computed value, dropping the oldest one once full.
+    pub fn push(&mut self, entry: HistoryEntry) {
+        if self.entries.len() == MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Export the entries to the given format.
+    pub fn export(&self, history_format: HistoryFormat) -> Result<String> {
+        match history_format {
+            HistoryFormat::Csv => {
+                let mut csv = format!("{CSV_HEADER}\n");
+                for entry in &self.entries {
+                    csv += &format!(
+                        "{},{},{},{}\n",
+                        csv_field(&entry.expression),
+                        entry.value,
+                        entry.timestamp,
+                        csv_field(&entry.mode)
+                    );
+                }
+                Ok(csv)
+            }
+            HistoryFormat::Json => Ok(serde_json::to_string_pretty(&self.entries)? + "\n"),
+        }
+    }
+
+    /// Rebuild a history from an exported file, keeping its most recent entries if too long.
+    pub fn import(history_format: HistoryFormat, contents: &str) -> Result<Self> {
+        let entries: Vec<HistoryEntry> = match history_format {
+            HistoryFormat::Csv => {
+                let mut records = csv_records(contents).into_iter();
+                if records
+                    .next()
+                    .is_none_or(|header| header.join(",").trim() != CSV_HEADER)
+                {
+                    return Err(format!("Expected the CSV header: {CSV_HEADER}").into());
+                }
+
+                records
+                    .map(|record| -> Result<HistoryEntry> {
+                        match record.as_slice() {
+                            [expression, value, timestamp, mode] => Ok(HistoryEntry {
+                                expression: expression.clone(),
+                                value: value.parse()?,
+                                timestamp: timestamp.parse()?,
+                                mode: mode.clone(),
+                            }),
+                            _ => {
+                                Err(format!("Invalid history record: {}", record.join(",")).into())
+                            }
+                        }
+                    })
+                    .collect::<Result<_>>()?
+            }
+            HistoryFormat::Json => serde_json::from_str(contents)?,
+        };
+
+        let mut history = Self::default();
+        for entry in entries {
+            history.push(entry);
+        }
+        Ok(history)
+    }
+}
+
+/// An action of the history panel, on files.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryFileAction {
+    #[default]
+    ExportCsv,
+    ExportJson,
+    /// Pick a file to import, on the Web (files are dropped on the window on desktop)
+    Import,
+}
+
+impl HistoryFileAction {
+    pub const ALL: [Self; 3] = [Self::ExportCsv, Self::ExportJson, Self::Import];
+
+    /// Identifier of the message labelling the action, in the translations.
+    pub const fn message_id(self) -> &'static str {
+        match self {
+            Self::ExportCsv => "history-csv",
+            Self::ExportJson => "history-json",
+            Self::Import => "history-import",
+        }
+    }
+}
+
+/// The history panel, shown beside the buttons on wide windows.
+#[derive(Component, Default, Clone)]
+pub struct HistoryPanel;
+
+/// Text listing the history entries, in the history panel.
+#[derive(Component, Default, Clone)]
+pub struct HistoryList;
+
+/// A button of the history panel, applying its file action when clicked.
+#[derive(Component, Default, Clone, Copy)]
+pub struct HistoryFileButton(pub HistoryFileAction);
+
+/// Keep the history of the computed values, exported to and imported from CSV and JSON files.
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<History>();
+        app.add_systems(
+            Update,
+            (
+                (record_results.after(apply_calc_actions), import_history),
+                update_history_list,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// The history panel, hidden until the window is wide enough (see [`crate::responsive`]).
+pub fn panel() -> impl Scene {
+    let buttons: Vec<Box<dyn Scene>> = HistoryFileAction::ALL
+        .into_iter()
+        .map(|action| Box::new(file_button(action)) as Box<dyn Scene>)
+        .collect();
+
+    bsn! {
+        Node {
+            display: Display::None,
+            width: Val::Percent(40.),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.),
+            padding: UiRect::all(Val::Px(12.)),
+        }
+        HistoryPanel
+        BackgroundColor::default()
+        ThemeRole::Panel
+        Children [
+            (
+                Node {
+                    flex_shrink: 0.,
+                    justify_content: JustifyContent::FlexEnd,
+                    column_gap: Val::Px(6.),
+                }
+                Children [{ buttons }]
+            ),
+            (
+                Node {
+                    flex_grow: 1.,
+                    min_height: Val::Px(0.),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::FlexEnd, // The most recent entries at the bottom
+                    overflow: Overflow::clip(),
+                }
+                Children [(
+                    HistoryList
+                    Text::default()
+                    TextFont::from_font_size(14.)
+                    TextColor::default()
+                    ThemeRole::PanelText
+                    TextLayout::justify(Justify::Right)
+                )]
+            ),
+        ]
+    }
+}
+
+fn file_button(action: HistoryFileAction) -> impl Scene {
+    // Files are dropped on the window on desktop
+    let display = if action == HistoryFileAction::Import && !cfg!(target_arch = "wasm32") {
+        Display::None
+    } else {
+        Display::Flex
+    };
+
+    bsn! {
+        Button
+        HistoryFileButton({action})
+        FileButton
+        Node {
+            display,
+            padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+            border: UiRect::all(Val::Px(2.)),
+            border_radius: BorderRadius::MAX,
+        }
+        BorderColor::default()
+        BackgroundColor::default()
+        on(on_history_file_click)
+        Children [(
+            Localized({action.message_id()})
+            Text::default()
+            TextFont::from_font_size(12.)
+            TextColor::default()
+            ThemeRole::PanelText
+        )]
+    }
+}
+
+/// Current time, in seconds since the Unix epoch.
+#[cfg(not(target_arch = "wasm32"))]
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Current time, in seconds since the Unix epoch.
+#[cfg(target_arch = "wasm32")]
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn now() -> u64 {
+    (js_sys::Date::now() / 1000.) as u64
+}
+
+/// Add the computed values to the history.
+#[allow(clippy::needless_pass_by_value)]
+fn record_results(
+    mut results: MessageReader<CalcResult>,
+    mut history: ResMut<History>,
+    layout: Res<ButtonLayout>,
+) {
+    for CalcResult(evaluation) in results.read() {
+        history.push(HistoryEntry {
+            expression: evaluation.expression.clone(),
+            value: evaluation.value,
+            timestamp: now(),
+            mode: layout.name.clone(),
+        });
+    }
+}
+
+/// Replace the history with the imported files, ignoring the files of other formats.
+fn import_history(mut files: MessageReader<OpenedFile>, mut history: ResMut<History>) {
+    for file in files.read() {
+        let Some(history_format) = file
+            .extension()
+            .as_deref()
+            .and_then(HistoryFormat::of_extension)
+        else {
+            debug!("Ignoring the file: {}", file.name);
+            continue;
+        };
+
+        match History::import(history_format, &file.contents) {
+            Ok(imported) => {
+                info!(
+                    "Imported {} history entries from: {}",
+                    imported.entries().len(),
+                    file.name
+                );
+
+                *history = imported;
+            }
+            Err(err) => warn!("Failed to import '{}': {err}", file.name),
+        }
+    }
+}
+
+/// Export the history, or pick a file to import, when clicking a button of the history panel.
+#[allow(clippy::needless_pass_by_value)]
+fn on_history_file_click(
+    click: On<Pointer<Click>>,
+    buttons: Query<&HistoryFileButton>,
+    history: Res<History>,
+) -> Result {
+    let HistoryFileButton(action) = *buttons.get(click.entity)?;
+
+    debug!("Clicking on history button: {action:?}");
+
+    let history_format = match action {
+        HistoryFileAction::ExportCsv => HistoryFormat::Csv,
+        HistoryFileAction::ExportJson => HistoryFormat::Json,
+        #[cfg(target_arch = "wasm32")]
+        HistoryFileAction::Import => return export::open(".csv,.json"),
+        #[cfg(not(target_arch = "wasm32"))]
+        HistoryFileAction::Import => return Ok(()),
+    };
+
+    export::save(
+        history_format.file_name(),
+        history_format.mime_type(),
+        &history.export(history_format)?,
+    )
+}
+
+/// List the history entries in the panel, when spawned or when changed.
+#[allow(clippy::needless_pass_by_value)]
+fn update_history_list(
+    history: Res<History>,
+    format: Res<NumberFormat>,
+    mut list: Query<&mut Text, With<HistoryList>>,
+) {
+    for mut text in &mut list {
+        if !history.is_changed() && !format.is_changed() && !text.is_added() {
+            continue;
+        }
+
+        text.0 = history
+            .entries()
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{} = {}",
+                    prettify(&entry.expression),
+                    format.format(entry.value)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
@@ -0,0 +1,227 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+use crate::button::{self, ButtonAction};
+use crate::config::CalcConfig;
+
+/// Maximum number of rows or columns of a layout grid.
+pub const MAX_GRID_SIZE: u16 = 16;
+
+/// Built-in layouts, by name.
+const PRESETS: [(&str, &str); 1] = [("basic", include_str!("../assets/layouts/basic.layout.ron"))];
+
+/// Deserialize a button action from its label, e.g. `7` or `+/-`.
+fn action<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ButtonAction, D::Error> {
+    let label = String::deserialize(deserializer)?;
+    button::from_label(&label)
+        .map(ButtonAction)
+        .ok_or_else(|| D::Error::custom(format!("Unknown button action: '{label}'")))
+}
+
+const fn single_span() -> u16 {
+    1
+}
+
+/// A button of a layout, placed on the grid.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LayoutButton {
+    /// Button variant applied when clicked, given by its label
+    #[serde(deserialize_with = "action")]
+    pub action: ButtonAction,
+    /// Row of the button, from 0 (the display row excluded)
+    pub row: u16,
+    /// Column of the button, from 0
+    pub column: u16,
+    #[serde(default = "single_span")]
+    pub row_span: u16,
+    #[serde(default = "single_span")]
+    pub column_span: u16,
+}
+
+/// Buttons of the calculator grid, loaded from a RON file (see the layouts in `assets/layouts`).
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ButtonLayout {
+    pub name: String,
+    /// Number of rows of the grid, the display row excluded
+    pub rows: u16,
+    pub columns: u16,
+    pub buttons: Vec<LayoutButton>,
+}
+
+impl Default for ButtonLayout {
+    fn default() -> Self {
+        Self::preset(PRESETS[0].0).expect("The built-in layouts are valid")
+    }
+}
+
+impl ButtonLayout {
+    /// Parse a layout from its RON definition, checking that the buttons fit in the grid (up to
+    /// [`MAX_GRID_SIZE`] rows and columns) without overlapping.
+    pub fn parse(ron: &str) -> Result<Self> {
+        let layout: Self = ron::from_str(ron)?;
+        if layout.rows > MAX_GRID_SIZE || layout.columns > MAX_GRID_SIZE {
+            return Err(format!(
+                "The {}x{} grid is larger than {MAX_GRID_SIZE}x{MAX_GRID_SIZE}",
+                layout.rows, layout.columns
+            )
+            .into());
+        }
+
+        let mut cells = vec![false; usize::from(layout.rows) * usize::from(layout.columns)];
+        for button in &layout.buttons {
+            let label = button.action.0;
+            if button.row_span == 0 || button.column_span == 0 {
+                return Err(format!("Button '{label}' has an empty span").into());
+            }
+            let (Some(row_end), Some(column_end)) = (
+                button.row.checked_add(button.row_span),
+                button.column.checked_add(button.column_span),
+            ) else {
+                return Err(format!("Button '{label}' has an overflowing span").into());
+            };
+            if row_end > layout.rows || column_end > layout.columns {
+                return Err(format!(
+                    "Button '{label}' is outside the {}x{} grid",
+                    layout.rows, layout.columns
+                )
+                .into());
+            }
+
+            for row in button.row..row_end {
+                for column in button.column..column_end {
+                    let cell = &mut cells
+                        [usize::from(row) * usize::from(layout.columns) + usize::from(column)];
+                    if *cell {
+                        return Err(format!("Button '{label}' overlaps another button").into());
+                    }
+                    *cell = true;
+                }
+            }
+        }
+
+        Ok(layout)
+    }
+
+    /// Get a built-in layout by name: `basic`.
+    pub fn preset(name: &str) -> Result<Self> {
+        let (_, ron) = PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .ok_or_else(|| format!("Unknown layout: '{name}'"))?;
+
+        Self::parse(ron)
+    }
+}
+
+/// Load the layout files (`.layout.ron`, the other RON files being left to their own loaders), e.g.
+/// `layouts/basic.layout.ron`.
+#[derive(Default, TypePath)]
+struct LayoutLoader;
+
+impl AssetLoader for LayoutLoader {
+    type Asset = ButtonLayout;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        ButtonLayout::parse(std::str::from_utf8(&bytes)?).map_err(|err| err.to_string().into())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["layout.ron"]
+    }
+}
+
+/// The layout file of the configured mode, watched for changes.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+struct LayoutFile(Handle<ButtonLayout>);
+
+/// Select the layout of the configured mode, and reload it when its file changes (not on the Web,
+/// which only has the built-in layouts).
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ButtonLayout>();
+        app.init_asset::<ButtonLayout>();
+        app.register_asset_loader(LayoutLoader);
+        app.add_systems(Startup, select_layout);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Startup, watch_layout_file);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_systems(Update, reload_layout);
+    }
+}
+
+/// Start with the built-in layout of the configured mode, if any.
+#[allow(clippy::needless_pass_by_value)]
+fn select_layout(config: Option<Res<CalcConfig>>, mut layout: ResMut<ButtonLayout>) {
+    // Without any configuration (e.g. in the tests), the default layout is kept
+    let Some(config) = config else {
+        return;
+    };
+
+    match ButtonLayout::preset(&config.layout) {
+        Ok(preset) => *layout = preset,
+        Err(err) if cfg!(target_arch = "wasm32") => error!("{err}"),
+        // Loaded from its file instead
+        Err(_) => {}
+    }
+}
+
+/// Load the layout file of the configured mode, e.g. `assets/layouts/basic.layout.ron`.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::needless_pass_by_value)]
+fn watch_layout_file(
+    mut commands: Commands,
+    config: Option<Res<CalcConfig>>,
+    asset_server: Res<AssetServer>,
+) {
+    if let Some(config) = config {
+        let handle = asset_server.load(format!("layouts/{}.layout.ron", config.layout));
+        commands.insert_resource(LayoutFile(handle));
+    }
+}
+
+/// Switch to the layout file once loaded, then each time it's modified.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::needless_pass_by_value)]
+pub fn reload_layout(
+    mut events: MessageReader<AssetEvent<ButtonLayout>>,
+    file: Option<Res<LayoutFile>>,
+    layouts: Res<Assets<ButtonLayout>>,
+    mut layout: ResMut<ButtonLayout>,
+) {
+    let Some(file) = file else {
+        return;
+    };
+
+    for event in events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event
+            && *id == file.0.id()
+            && let Some(loaded) = layouts.get(*id)
+            && layout.set_if_neq(loaded.clone())
+        {
+            info!("Loaded the layout: {}", loaded.name);
+        }
+    }
+}
@@ -0,0 +1,129 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use std::fmt;
+
+/// Locale presets, defining the decimal and digit group separators.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    EnUs,
+    EnGb,
+    FrFr,
+    DeDe,
+    DeCh,
+    EsEs,
+    ItIt,
+    NlNl,
+    PtBr,
+}
+
+impl Locale {
+    pub const ALL: [Self; 9] = [
+        Self::EnUs,
+        Self::EnGb,
+        Self::FrFr,
+        Self::DeDe,
+        Self::DeCh,
+        Self::EsEs,
+        Self::ItIt,
+        Self::NlNl,
+        Self::PtBr,
+    ];
+
+    /// BCP 47 language tag of the locale, e.g. `en-US`.
+    pub const fn tag(self) -> &'static str {
+        match self {
+            Self::EnUs => "en-US",
+            Self::EnGb => "en-GB",
+            Self::FrFr => "fr-FR",
+            Self::DeDe => "de-DE",
+            Self::DeCh => "de-CH",
+            Self::EsEs => "es-ES",
+            Self::ItIt => "it-IT",
+            Self::NlNl => "nl-NL",
+            Self::PtBr => "pt-BR",
+        }
+    }
+
+    /// Language of the locale, e.g. `en`.
+    pub const fn language(self) -> &'static str {
+        match self {
+            Self::EnUs | Self::EnGb => "en",
+            Self::FrFr => "fr",
+            Self::DeDe | Self::DeCh => "de",
+            Self::EsEs => "es",
+            Self::ItIt => "it",
+            Self::NlNl => "nl",
+            Self::PtBr => "pt",
+        }
+    }
+
+    /// Next locale (settings panel).
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|locale| *locale == self)
+            .unwrap_or_default();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub const fn decimal_separator(self) -> char {
+        match self {
+            Self::EnUs | Self::EnGb | Self::DeCh => '.',
+            Self::FrFr | Self::DeDe | Self::EsEs | Self::ItIt | Self::NlNl | Self::PtBr => ',',
+        }
+    }
+
+    pub const fn group_separator(self) -> char {
+        match self {
+            Self::EnUs | Self::EnGb => ',',
+            // Narrow no-break space
+            Self::FrFr => '\u{202F}',
+            Self::DeDe | Self::EsEs | Self::ItIt | Self::NlNl | Self::PtBr => '.',
+            // Right single quotation mark
+            Self::DeCh => '\u{2019}',
+        }
+    }
+
+    /// Find the preset matching a language tag (e.g. `fr-FR`, `fr_FR.UTF-8` or `fr`),
+    /// falling back to the first preset of the same language.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag
+            .split('.')
+            .next()
+            .unwrap_or_default()
+            .replace('_', "-")
+            .to_ascii_lowercase();
+        let language = tag.split('-').next().unwrap_or_default();
+
+        Self::ALL
+            .into_iter()
+            .find(|locale| locale.tag().to_ascii_lowercase() == tag)
+            .or_else(|| {
+                Self::ALL
+                    .into_iter()
+                    .find(|locale| locale.tag().split('-').next() == Some(language))
+            })
+    }
+
+    /// Detect the locale from the OS, or from the browser language on the Web.
+    pub fn detect() -> Self {
+        let tag = std::cfg_select! {
+            target_arch = "wasm32" => web_sys::window().and_then(|window| window.navigator().language()),
+            _ => sys_locale::get_locale(),
+        };
+
+        tag.as_deref().and_then(Self::from_tag).unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.tag())
+    }
+}
@@ -0,0 +1,347 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+use crate::button::{
+    ADD_BUTTON, ButtonAction, ButtonVariant, CLEAR_BUTTON, CalcButton, DIVIDE_BUTTON, DOT_BUTTON,
+    EQUAL_BUTTON, INVERT_BUTTON, MULTIPLY_BUTTON, POURCENT_BUTTON, SUB_BUTTON,
+};
+use crate::format::NumberFormat;
+use crate::theme::ThemeRole;
+
+/// Built-in translations, by language, English first as the fallback of the other ones.
+pub const PRESETS: [(&str, &str); 7] = [
+    ("en", include_str!("../assets/locales/en.ftl")),
+    ("de", include_str!("../assets/locales/de.ftl")),
+    ("es", include_str!("../assets/locales/es.ftl")),
+    ("fr", include_str!("../assets/locales/fr.ftl")),
+    ("it", include_str!("../assets/locales/it.ftl")),
+    ("nl", include_str!("../assets/locales/nl.ftl")),
+    ("pt", include_str!("../assets/locales/pt.ftl")),
+];
+
+/// Identifier of the message naming a button, the digits being named as is.
+pub fn button_message_id(button: ButtonVariant) -> Option<&'static str> {
+    match button {
+        CLEAR_BUTTON => Some("button-clear"),
+        INVERT_BUTTON => Some("button-invert"),
+        POURCENT_BUTTON => Some("button-percent"),
+        DIVIDE_BUTTON => Some("button-divide"),
+        MULTIPLY_BUTTON => Some("button-multiply"),
+        SUB_BUTTON => Some("button-subtract"),
+        ADD_BUTTON => Some("button-add"),
+        EQUAL_BUTTON => Some("button-equals"),
+        DOT_BUTTON => Some("button-decimal"),
+        _ => None,
+    }
+}
+
+fn parse_resource(source: &str) -> Result<FluentResource> {
+    FluentResource::try_new(source.to_string())
+        .map_err(|(_, errors)| format!("Invalid translations: {errors:?}").into())
+}
+
+/// A translation file (`.ftl`), in the Fluent syntax.
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct Translations(pub String);
+
+/// The texts of the UI in the current language, the missing ones falling back to English.
+#[derive(Resource)]
+pub struct Localization {
+    language: String,
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self::preset(PRESETS[0].0)
+    }
+}
+
+impl Localization {
+    /// Localize in a language (e.g. `fr`), given its translations in the Fluent syntax.
+    pub fn new(language: &str, translations: &str) -> Result<Self> {
+        let mut bundle =
+            FluentBundle::new_concurrent(vec![language.parse::<LanguageIdentifier>()?]);
+        // No Unicode isolation marks around the arguments, which the UI font doesn't render
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(parse_resource(PRESETS[0].1)?)
+            .map_err(|errors| format!("Invalid translations: {errors:?}"))?;
+        bundle.add_resource_overriding(parse_resource(translations)?);
+
+        Ok(Self {
+            language: language.to_string(),
+            bundle,
+        })
+    }
+
+    /// Localize with the built-in translations of a language, English if unknown.
+    pub fn preset(language: &str) -> Self {
+        let (language, translations) = PRESETS
+            .into_iter()
+            .find(|(preset, _)| *preset == language)
+            .unwrap_or(PRESETS[0]);
+
+        Self::new(language, translations).expect("The built-in translations should be valid")
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// The text of a message, or its identifier if missing.
+    pub fn text(&self, id: &str) -> String {
+        self.text_with(id, &[])
+    }
+
+    /// The text of a message given its arguments (e.g. `$decimals`), or its identifier if missing.
+    pub fn text_with(&self, id: &str, args: &[(&str, String)]) -> String {
+        let Some(pattern) = self
+            .bundle
+            .get_message(id)
+            .and_then(|message| message.value())
+        else {
+            warn!("Missing translation: {id}");
+            return id.to_string();
+        };
+
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        let mut errors = Vec::new();
+        let text = self
+            .bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors)
+            .into_owned();
+        if !errors.is_empty() {
+            warn!("Failed to translate {id}: {errors:?}");
+        }
+
+        text
+    }
+
+    /// Name of a button, as shown in its tooltip and spoken by the screen readers.
+    pub fn button_name(&self, button: ButtonVariant) -> String {
+        button_message_id(button).map_or_else(|| button.to_string(), |id| self.text(id))
+    }
+}
+
+/// The language of the UI (e.g. `fr`), independent of the locale of the numbers, the language of
+/// the locale if none.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UiLanguage(pub Option<&'static str>);
+
+impl UiLanguage {
+    /// Next language of the built-in translations, back to the language of the locale after the
+    /// last one (settings panel).
+    pub fn next(self) -> Self {
+        let index = self
+            .0
+            .and_then(|language| PRESETS.iter().position(|(preset, _)| *preset == language));
+        match index {
+            None => Self(Some(PRESETS[0].0)),
+            Some(index) => Self(PRESETS.get(index + 1).map(|(language, _)| *language)),
+        }
+    }
+}
+
+/// A text localized after the identifier of its message in the translations.
+#[derive(Component, Default, Clone, Copy)]
+pub struct Localized(pub &'static str);
+
+/// Text naming the hovered button, on the display.
+#[derive(Component, Default, Clone)]
+pub struct ButtonTooltip;
+
+/// Load the translation files (`.ftl`), e.g. `locales/fr.ftl`.
+#[derive(Default, TypePath)]
+struct TranslationsLoader;
+
+impl AssetLoader for TranslationsLoader {
+    type Asset = Translations;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        Ok(Translations(String::from_utf8(bytes)?))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ftl"]
+    }
+}
+
+/// The translation file of the current language, watched for changes.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Resource)]
+struct TranslationsFile {
+    language: String,
+    handle: Handle<Translations>,
+}
+
+/// Localize the UI in the language selected in the settings (the language of the locale by
+/// default), switched at runtime, and reload the translations when their file changes (not on the
+/// Web, which only has the built-in translations).
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Localization>();
+        app.init_resource::<UiLanguage>();
+        app.init_asset::<Translations>();
+        app.register_asset_loader(TranslationsLoader);
+        app.add_systems(
+            Update,
+            (
+                select_language,
+                #[cfg(not(target_arch = "wasm32"))]
+                (watch_translations_file, reload_translations),
+                (localize_texts, localize_window_title, update_button_tooltip),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// The tooltip of the hovered button, to be placed on the display.
+pub fn tooltip() -> impl Scene {
+    bsn! {
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(4.),
+            right: Val::Percent(8.),
+        }
+        ButtonTooltip
+        Text::default()
+        TextFont::from_font_size(10.)
+        TextColor::default()
+        ThemeRole::SecondaryText
+    }
+}
+
+/// Switch to the built-in translations of the selected language, or of the language of the locale,
+/// when changed.
+#[allow(clippy::needless_pass_by_value)]
+fn select_language(
+    format: Res<NumberFormat>,
+    ui_language: Res<UiLanguage>,
+    mut localization: ResMut<Localization>,
+) {
+    let language = ui_language.0.unwrap_or(format.locale.language());
+    if localization.language() != language {
+        info!("Switching the language to: {language}");
+
+        *localization = Localization::preset(language);
+    }
+}
+
+/// Load the translation file of the current language, e.g. `assets/locales/fr.ftl`.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::needless_pass_by_value)]
+fn watch_translations_file(
+    mut commands: Commands,
+    localization: Res<Localization>,
+    file: Option<Res<TranslationsFile>>,
+    asset_server: Res<AssetServer>,
+) {
+    if file.is_some_and(|file| file.language == localization.language()) {
+        return;
+    }
+
+    let language = localization.language().to_string();
+    let handle = asset_server.load(format!("locales/{language}.ftl"));
+    commands.insert_resource(TranslationsFile { language, handle });
+}
+
+/// Switch to the translation file once loaded, then each time it's modified.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::needless_pass_by_value)]
+fn reload_translations(
+    mut events: MessageReader<AssetEvent<Translations>>,
+    file: Option<Res<TranslationsFile>>,
+    translations: Res<Assets<Translations>>,
+    mut localization: ResMut<Localization>,
+) {
+    let Some(file) = file else {
+        return;
+    };
+
+    for event in events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event
+            && *id == file.handle.id()
+            && let Some(Translations(source)) = translations.get(*id)
+            && file.language == localization.language()
+        {
+            match Localization::new(&file.language, source) {
+                Ok(loaded) => {
+                    info!("Loaded the translations: {}", file.language);
+
+                    *localization = loaded;
+                }
+                Err(err) => warn!("Failed to load the translations '{}': {err}", file.language),
+            }
+        }
+    }
+}
+
+/// Localize the texts, when spawned or when the language changes.
+#[allow(clippy::needless_pass_by_value)]
+fn localize_texts(localization: Res<Localization>, mut texts: Query<(Ref<Localized>, &mut Text)>) {
+    for (localized, mut text) in &mut texts {
+        if localization.is_changed() || localized.is_added() {
+            text.0 = localization.text(localized.0);
+        }
+    }
+}
+
+/// Localize the title of the window, when the language changes.
+#[allow(clippy::needless_pass_by_value)]
+fn localize_window_title(
+    localization: Res<Localization>,
+    window: Option<Single<&mut Window, With<PrimaryWindow>>>,
+) {
+    if let Some(mut window) = window
+        && localization.is_changed()
+    {
+        window.title = localization.text("app-title");
+    }
+}
+
+/// Name the hovered button on the display.
+#[allow(clippy::needless_pass_by_value)]
+fn update_button_tooltip(
+    localization: Res<Localization>,
+    buttons: Query<(&ButtonAction, &Interaction), With<CalcButton>>,
+    mut tooltip: Single<&mut Text, With<ButtonTooltip>>,
+) {
+    let text = buttons
+        .iter()
+        .find(|(_, interaction)| **interaction == Interaction::Hovered)
+        .map(|(ButtonAction(button), _)| localization.button_name(*button))
+        .unwrap_or_default();
+
+    if tooltip.0 != text {
+        tooltip.0 = text;
+    }
+}
@@ -5,45 +5,100 @@
  *
  */
 
+mod accessibility;
+mod action;
 mod button;
+mod click;
+mod clipboard;
+mod config;
+mod engine;
+mod export;
+mod expression;
+mod expression_line;
+mod format;
+mod history;
+mod layout;
+mod locale;
+mod localization;
+mod mini;
 mod operation;
+#[cfg(not(target_arch = "wasm32"))]
+mod recorder;
+mod responsive;
+#[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+mod rpc;
+mod settings;
+mod speech;
+mod tape;
+#[cfg(test)]
+mod tests;
+mod theme;
+mod title_bar;
+#[cfg(feature = "tui")]
+mod tui;
+mod viewport;
+#[cfg(target_arch = "wasm32")]
+mod web;
 
 use bevy::input::keyboard::Key;
 use bevy::input_focus::directional_navigation::DirectionalNavigationPlugin;
 use bevy::input_focus::{FocusCause, InputFocus, InputFocusVisible, IsFocused, IsFocusedHelper};
 use bevy::math::CompassOctant;
 use bevy::prelude::*;
-use bevy::text::{EditableText, EditableTextFilter, TextEdit, TextEditChange};
+use bevy::text::{EditableText, EditableTextFilter, TextEditChange};
 use bevy::window::CompositeAlphaMode;
-use bevy::window::WindowResolution;
+use bevy::window::{WindowResizeConstraints, WindowResolution};
 
+use accessibility::AccessibilityPlugin;
+use action::{ActionPlugin, CalcAction};
 use button::{
-    ADD_BUTTON, ButtonVariant, CLEAR_BUTTON, CalcButton, DIVIDE_BUTTON, DOT_BUTTON, EIGHT_BUTTON,
-    EQUAL_BUTTON, FIVE_BUTTON, FOCUSED_BUTTON, FOUR_BUTTON, HOVERED_BUTTON, INVERT_BUTTON,
-    MULTIPLY_BUTTON, NINE_BUTTON, NORMAL_BUTTON, ONE_BUTTON, POURCENT_BUTTON, PRESSED_BUTTON,
-    SEVEN_BUTTON, SIX_BUTTON, SUB_BUTTON, THREE_BUTTON, TWO_BUTTON, ZERO_BUTTON,
+    ButtonAction, ButtonCategory, ButtonKind, ButtonVariant, CalcButton, DOT_BUTTON, EQUAL_BUTTON,
 };
-use operation::{CalcOperator, OperationMetadata};
+use click::ClickPlugin;
+use clipboard::ClipboardPlugin;
+use config::CalcConfig;
+use export::ExportPlugin;
+use expression_line::ExpressionLinePlugin;
+use format::NumberFormat;
+use history::HistoryPlugin;
+use layout::{ButtonLayout, LayoutButton, LayoutPlugin};
+use locale::Locale;
+use localization::LocalizationPlugin;
+use mini::{MiniModePlugin, keep_mini_layout};
+use operation::OperationMetadata;
+use responsive::{DESIGN_SIZE, ResponsivePlugin};
+use settings::SettingsPlugin;
+use speech::SpeechPlugin;
+use tape::TapePlugin;
+use theme::{Theme, ThemePlugin, ThemeRole};
+use title_bar::TitleBarPlugin;
+use viewport::{DisplayViewport, MAX_FONT_SIZE, ViewportPlugin};
 
 struct AppPlugin;
 
 #[derive(Component, Default, Clone)]
 struct InitialFocus;
 
-const N_COLS: u16 = 4;
-const N_ROWS: u16 = 6;
+/// The grid holding the display and the buttons.
+#[derive(Component, Default, Clone)]
+struct CalcGrid;
 
 impl AppPlugin {
-    fn window_plugin() -> WindowPlugin {
+    fn window_plugin(config: &CalcConfig) -> WindowPlugin {
         WindowPlugin {
             primary_window: Some(Window {
                 title: "Bevy Calculator".to_string(),
-                resolution: WindowResolution::new(330, 315),
-                resizable: false,
+                resolution: WindowResolution::new(config.width, config.height),
+                resizable: true,
+                resize_constraints: WindowResizeConstraints {
+                    min_width: DESIGN_SIZE.x / 2.,
+                    min_height: DESIGN_SIZE.y / 2.,
+                    ..Default::default()
+                },
                 transparent: true,
                 decorations: false,
-                canvas: Some("#bevy-canvas".into()),
-                fit_canvas_to_parent: false,
+                canvas: Some(config.canvas.clone()),
+                fit_canvas_to_parent: config.fit_canvas_to_parent,
                 composite_alpha_mode: std::cfg_select! {
                     target_os = "macos" => CompositeAlphaMode::PostMultiplied,
                     target_os = "linux" => CompositeAlphaMode::PreMultiplied,
@@ -58,78 +113,141 @@ impl AppPlugin {
 
 impl Plugin for AppPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(DefaultPlugins.set(Self::window_plugin()));
-        app.add_plugins(DirectionalNavigationPlugin);
+        let config = CalcConfig::load();
+
+        app.add_plugins(DefaultPlugins.set(Self::window_plugin(&config)));
+        app.insert_resource(NumberFormat {
+            locale: Locale::detect(),
+            ..default()
+        });
+        app.insert_resource(Theme::load(&config.theme).unwrap_or_else(|err| {
+            error!("Failed to load the theme '{}': {err}", config.theme);
+            Theme::default()
+        }));
+        app.insert_resource(config);
+        app.add_plugins(CalculatorPlugin);
+        #[cfg(target_arch = "wasm32")]
+        app.add_plugins(web::WebPlugin);
+        #[cfg(all(feature = "rpc", not(target_arch = "wasm32")))]
+        app.add_plugins(rpc::RpcPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(recorder::RecorderPlugin);
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_plugins(mini::MiniPositionPlugin);
         app.insert_resource(ClearColor(Color::NONE));
+    }
+}
+
+/// The calculator scene and systems, independent of the window and the rendering.
+struct CalculatorPlugin;
+
+impl Plugin for CalculatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(DirectionalNavigationPlugin);
+        app.add_plugins(ActionPlugin);
+        app.add_plugins(ThemePlugin);
+        app.add_plugins(LocalizationPlugin);
+        app.add_plugins(SettingsPlugin);
+        app.add_plugins(ViewportPlugin);
+        app.add_plugins(ExpressionLinePlugin);
+        app.add_plugins(LayoutPlugin);
+        app.add_plugins(ExportPlugin);
+        app.add_plugins(HistoryPlugin);
+        app.add_plugins(ResponsivePlugin);
+        app.add_plugins(TitleBarPlugin);
+        app.add_plugins(MiniModePlugin);
+        app.add_plugins(ClipboardPlugin);
+        app.add_plugins(TapePlugin);
+        app.add_plugins(AccessibilityPlugin);
+        app.add_plugins(SpeechPlugin);
+        app.add_plugins(ClickPlugin);
         app.add_systems(Startup, calc_setup);
-        app.add_systems(Update, (keyboard_input, button_state, buttons_state));
+        app.add_systems(
+            Update,
+            (
+                (spawn_layout_buttons, attach_layout_buttons)
+                    .chain()
+                    .after(keep_mini_layout),
+                keyboard_input,
+                button_state,
+                buttons_state,
+                localize_button_labels,
+            ),
+        );
         app.add_observer(sync_display_to_operand);
     }
 }
 
-fn calc_setup(mut commands: Commands) {
-    let button_labels: [ButtonVariant; 19] = [
-        // Row 1
-        CLEAR_BUTTON,
-        INVERT_BUTTON,
-        POURCENT_BUTTON,
-        DIVIDE_BUTTON,
-        // Row 2
-        SEVEN_BUTTON,
-        EIGHT_BUTTON,
-        NINE_BUTTON,
-        MULTIPLY_BUTTON,
-        // Row 3
-        FOUR_BUTTON,
-        FIVE_BUTTON,
-        SIX_BUTTON,
-        SUB_BUTTON,
-        // Row 4
-        ONE_BUTTON,
-        TWO_BUTTON,
-        THREE_BUTTON,
-        ADD_BUTTON,
-        // Row 5
-        ZERO_BUTTON,
-        DOT_BUTTON,
-        EQUAL_BUTTON,
-    ];
-
-    #[allow(
-        clippy::cast_possible_truncation,
-        clippy::cast_possible_wrap,
-        clippy::explicit_auto_deref
-    )]
-    let buttons: Vec<Box<dyn Scene>> = button_labels
-        .iter()
-        .enumerate()
-        .map(|(i, label)| {
-            let row = (i / 4) as u16;
-            let col = (i % 4) as u16;
-            button_scene(*label, row, col, i == 0)
-        })
-        .collect();
+impl CalculatorPlugin {
+    /// Build an app running the calculator without any window nor GPU (tests, headless replays).
+    #[cfg(any(test, not(target_arch = "wasm32")))]
+    fn headless_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            bevy::scene::ScenePlugin,
+            bevy::input::InputPlugin,
+            bevy::input_focus::InputDispatchPlugin,
+            bevy::text::TextPlugin,
+            bevy::ui::UiPlugin,
+            Self,
+        ));
+        // Without any audio output, the click sounds are still generated
+        app.init_asset::<bevy::audio::AudioSource>();
+
+        app
+    }
+}
 
-    commands.spawn_scene_list(bsn_list![Camera2d, grid(buttons),]);
+fn calc_setup(mut commands: Commands) {
+    // The buttons are added by `spawn_layout_buttons`
+    commands.spawn_scene_list(bsn_list![Camera2d, root(),]);
 }
 
+/// Tracks of the same size, sharing the whole grid.
 fn grid_tracks(count: u16) -> Vec<RepeatedGridTrack> {
-    vec![RepeatedGridTrack::auto(count); count as usize]
+    vec![RepeatedGridTrack::flex(count, 1.)]
 }
 
-fn grid(buttons: Vec<Box<dyn Scene>>) -> impl Scene {
+/// The whole window: the title bar, then the calculator grid, and the history panel beside it on
+/// wide windows.
+fn root() -> impl Scene {
     bsn! {
         Node {
-            display: Display::Grid,
             width: Val::Percent(100.),
             height: Val::Percent(100.),
-            grid_template_columns: { grid_tracks(N_COLS) },
-            grid_template_rows: { grid_tracks(N_ROWS) },
+            flex_direction: FlexDirection::Column,
         }
-        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8))
+        BackgroundColor::default()
+        ThemeRole::Background
+        Children [
+            title_bar::title_bar(),
+            (
+                Node {
+                    flex_grow: 1.,
+                    min_height: Val::Px(0.),
+                }
+                Children [
+                    grid(),
+                    history::panel(),
+                ]
+            ),
+        ]
+    }
+}
+
+fn grid() -> impl Scene {
+    bsn! {
+        Node {
+            display: Display::Grid,
+            flex_grow: 1.,
+        }
+        CalcGrid
         Children [
             display(),
-            { buttons },
+            settings::panel(),
+            tape::panel(),
         ]
     }
 }
@@ -138,155 +256,180 @@ fn display() -> impl Scene {
     bsn! {
         Node {
             display: Display::Grid,
-            grid_column: GridPlacement::span(4),
+            grid_column: GridPlacement::start_end(1, -1), // Whole row
             padding: UiRect::right(Val::Percent(3.)),
         }
-        Children [(
-            Node {
-                border: UiRect::all(Val::Px(2.)),
-                border_radius: BorderRadius::MAX,
-                margin: UiRect::all(Val::Percent(1.)),
-                justify_content: JustifyContent::Center, // Horizontal
-                align_items: AlignItems::Center,         // Vertical
-            }
-            BorderColor::all(Color::BLACK)
-            BackgroundColor(Color::srgb(0.25, 0.25, 0.25))
-            Children [(
+        Children [
+            (
                 Node {
-                    width: Val::Percent(90.),
+                    border: UiRect::all(Val::Px(2.)),
+                    border_radius: BorderRadius::MAX,
+                    margin: UiRect::all(Val::Percent(1.)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center, // Vertical
+                    align_items: AlignItems::Center,         // Horizontal
                 }
-                TextColor::WHITE
-                TextLayout::justify(Justify::Center)
-                EditableText::new("0")
-                EditableTextFilter::new(is_calc_char)
-                OperationMetadata::default()
-            )]
-        )]
+                BorderColor::default()
+                BackgroundColor::default()
+                ThemeRole::Display
+                Children [
+                    expression_line::expression_line(),
+                    (
+                        Node {
+                            width: Val::Percent(80.),
+                            overflow: Overflow::scroll_x(),
+                        }
+                        DisplayViewport
+                        ScrollPosition::default()
+                        on(viewport::scroll_display)
+                        Children [(
+                            Node {
+                                min_width: Val::Percent(100.),
+                            }
+                            TextColor::default()
+                            ThemeRole::DisplayText
+                            TextFont::from_font_size(MAX_FONT_SIZE)
+                            TextLayout::new(Justify::Center, LineBreak::NoWrap)
+                            EditableText::new("0")
+                            EditableTextFilter::new(is_calc_char)
+                            OperationMetadata::default()
+                        )]
+                    ),
+                    viewport::overflow_marker(false),
+                    viewport::overflow_marker(true),
+                ]
+            ),
+            settings::indicator(),
+            localization::tooltip(),
+        ]
     }
 }
 
-#[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
-fn button_scene(label: ButtonVariant, row: u16, col: u16, is_first: bool) -> Box<dyn Scene> {
-    let grid_row = GridPlacement::start_end(row as i16 + 2, row as i16 + 3); // Offset by 1 for the result value row
-    let grid_column = GridPlacement::start_end(col as i16 + 1, col as i16 + 2);
+fn button_scene(button: &LayoutButton, is_first: bool) -> Result<Box<dyn Scene>> {
+    let ButtonAction(label) = button.action;
+    let kind = ButtonKind::of(label);
+    // The grid lines start at 1, offset by 1 for the result value row
+    let line = |position: u16, offset: u16| {
+        position
+            .checked_add(offset)
+            .and_then(|line| i16::try_from(line).ok())
+            .ok_or_else(|| format!("Button '{label}' is outside the grid"))
+    };
+    let grid_row = GridPlacement::start_span(line(button.row, 2)?, button.row_span);
+    let grid_column = GridPlacement::start_span(line(button.column, 1)?, button.column_span);
 
-    if is_first {
+    Ok(if is_first {
         Box::new(bsn! {
-            @CalcButton { @label: label }
+            @CalcButton { @label: label, @kind: kind }
             Node { grid_row, grid_column }
             InitialFocus
             on(on_button_click)
         })
     } else {
         Box::new(bsn! {
-            @CalcButton { @label: label }
+            @CalcButton { @label: label, @kind: kind }
             Node { grid_row, grid_column }
             on(on_button_click)
         })
-    }
+    })
 }
 
-/// Filter callback for the calculator display, allowing only valid input characters.
-const fn is_calc_char(c: char) -> bool {
-    c.is_ascii_digit() || c == '.' || c == '-'
-}
+/// Lay out the buttons on the grid, when spawned or when the layout changes (e.g. its file is
+/// modified).
+#[allow(clippy::needless_pass_by_value)]
+fn spawn_layout_buttons(
+    mut commands: Commands,
+    layout: Res<ButtonLayout>,
+    grid: Single<(Ref<CalcGrid>, &mut Node)>,
+    buttons: Query<Entity, With<CalcButton>>,
+) {
+    let (grid, mut node) = grid.into_inner();
+    if !layout.is_changed() && !grid.is_added() {
+        return;
+    }
 
-/// Replace the editable text and move the cursor to the end.
-fn reset_editable(editable: &mut EditableText, text: &str) {
-    editable.editor_mut().set_text(text);
-    editable.queue_edit(TextEdit::TextEnd(false));
-}
+    debug!("Laying out the buttons after the layout: {}", layout.name);
 
-/// Process a button action (digit, operator, etc.) and update the display and operation state
-fn process_button_action(
-    button: &str,
-    editable: &mut EditableText,
-    op_metadata: &mut OperationMetadata,
-) -> Result {
-    match button {
-        // Digit buttons
-        ZERO_BUTTON | ONE_BUTTON | TWO_BUTTON | THREE_BUTTON | FOUR_BUTTON | FIVE_BUTTON
-        | SIX_BUTTON | SEVEN_BUTTON | EIGHT_BUTTON | NINE_BUTTON => {
-            let current = editable.value();
-            if current == "0" {
-                reset_editable(editable, button);
-            } else {
-                editable.queue_edit(TextEdit::Insert(button.into()));
-            }
-        }
+    for button in &buttons {
+        commands.entity(button).despawn();
+    }
 
-        // Operator buttons
-        CLEAR_BUTTON => {
-            reset_editable(editable, "0");
-            op_metadata.reset();
-        }
-        INVERT_BUTTON => {
-            let current = editable.value().to_string();
-            let negated = (!current.is_empty() && current != "0").then(|| format!("-{current}"));
-            let new_text = current
-                .strip_prefix('-')
-                .map(str::to_string)
-                .or(negated)
-                .unwrap_or(current);
-            reset_editable(editable, &new_text);
-        }
-        POURCENT_BUTTON => {
-            let current = editable.value().to_string();
-            let display_value = current.parse::<f64>()?;
-            let result_value = display_value / 100.0;
+    node.grid_template_columns = grid_tracks(layout.columns);
+    node.grid_template_rows = grid_tracks(layout.rows + 1); // Plus the result value row
 
-            info!("Calculating: {display_value} % = {result_value}");
+    let buttons: Vec<Box<dyn Scene>> = layout
+        .buttons
+        .iter()
+        .enumerate()
+        .filter_map(|(i, button)| {
+            button_scene(button, i == 0)
+                .inspect_err(|err| warn!("Failed to lay out a button: {err}"))
+                .ok()
+        })
+        .collect();
+    commands.spawn_scene_list(buttons);
+}
 
-            reset_editable(editable, &result_value.to_string());
-            op_metadata.reset();
-        }
-        ADD_BUTTON | SUB_BUTTON | MULTIPLY_BUTTON | DIVIDE_BUTTON => {
-            let current = editable.value().to_string();
+/// Attach the buttons spawned by `spawn_layout_buttons` to the grid, between the display and the
+/// settings panel.
+#[allow(clippy::needless_pass_by_value)]
+fn attach_layout_buttons(
+    mut commands: Commands,
+    grid: Single<Entity, With<CalcGrid>>,
+    buttons: Query<Entity, (With<CalcButton>, Without<ChildOf>)>,
+) {
+    let buttons: Vec<Entity> = buttons.iter().collect();
+    if !buttons.is_empty() {
+        commands.entity(*grid).insert_children(1, &buttons);
+    }
+}
 
-            // Handle the case the user clicks on an operator before clicking on number buttons
-            if op_metadata.left_operand().is_none() {
-                op_metadata.set_left_operand(&current)?;
-            }
+/// Filter callback for the calculator display, allowing only valid input characters (both
+/// separators are accepted, then normalized after the locale by [`sync_display_to_operand`]).
+const fn is_calc_char(c: char) -> bool {
+    c.is_ascii_digit() || c == '.' || c == ',' || c == '-'
+}
 
-            let operator = match button {
-                ADD_BUTTON => CalcOperator::Add,
-                SUB_BUTTON => CalcOperator::Sub,
-                MULTIPLY_BUTTON => CalcOperator::Mul,
-                DIVIDE_BUTTON => CalcOperator::Div,
-                _ => unreachable!(),
-            };
-            op_metadata.set_operator(operator);
-
-            // Clear the display for the next operand. The TextEditChange observer
-            // will sync the cleared value into the appropriate operand.
-            reset_editable(editable, "0");
-        }
-        DOT_BUTTON => {
-            let current = editable.value().to_string();
-            if !current.contains('.') {
-                editable.queue_edit(TextEdit::Insert(".".into()));
-            }
-        }
-        EQUAL_BUTTON if op_metadata.is_under_operation() => {
-            let result_value = op_metadata.calculate()?;
-            reset_editable(editable, &result_value.to_string());
-            op_metadata.reset();
+/// Label the decimal button with the decimal separator of the locale, when spawned or when the locale changes.
+#[allow(clippy::needless_pass_by_value)]
+fn localize_button_labels(
+    format: Res<NumberFormat>,
+    buttons: Query<(Ref<ButtonAction>, &Children), With<CalcButton>>,
+    mut text_query: Query<&mut Text, Without<OperationMetadata>>,
+) {
+    for (action, children) in &buttons {
+        if action.0 == DOT_BUTTON
+            && (format.is_changed() || action.is_added())
+            && let Ok(mut text) = text_query.get_mut(children[0])
+        {
+            text.0 = format.decimal_separator().to_string();
         }
-
-        _ => {}
     }
-
-    Ok(())
 }
 
-/// Sync the current operation operand with the editable display value.
+/// Sync the current operation operand with the editable display value, dropping the digits typed
+/// beyond the maximum input length, and normalizing the typed separators.
+#[allow(clippy::needless_pass_by_value)]
 fn sync_display_to_operand(
     _change: On<TextEditChange>,
-    mut display: Single<(&EditableText, &mut OperationMetadata)>,
+    mut display: Single<(&mut EditableText, &mut OperationMetadata)>,
+    format: Res<NumberFormat>,
 ) {
     let (editable, op_metadata) = &mut *display;
-    let value = editable.value().to_string();
-    let _ = op_metadata.set_operand(&value);
+    let mut value = editable.value().to_string();
+    // Only the typed text is limited, not the one written by the calculator (e.g. a result)
+    if !op_metadata.is_shown(&value) {
+        let typed = format
+            .truncate_input(&value)
+            .unwrap_or_else(|| value.clone());
+        let typed = format.normalize_input(&typed).unwrap_or(typed);
+        if typed != value {
+            action::reset_editable(editable, &typed);
+            value = typed;
+        }
+    }
+
+    op_metadata.sync_operand(&value, &format);
 }
 
 /// Handle keyboard input for calculator navigation and the Enter-to-activate shortcut.
@@ -297,14 +440,11 @@ fn sync_display_to_operand(
 fn keyboard_input(
     keys: Res<ButtonInput<KeyCode>>,
     logical_keys: Res<ButtonInput<Key>>,
-    mut operation_query: Query<
-        (&mut EditableText, &mut OperationMetadata),
-        With<OperationMetadata>,
-    >,
+    mut actions: MessageWriter<CalcAction>,
     mut input_focus_visible: ResMut<InputFocusVisible>,
-    button_query: Query<(Entity, &Children), With<CalcButton>>,
+    button_query: Query<&ButtonAction, With<CalcButton>>,
     initial_focus_query: Query<Entity, (With<CalcButton>, With<InitialFocus>)>,
-    text_query: Query<&Text, Without<OperationMetadata>>,
+    text_fields: Query<(), (With<EditableText>, Without<OperationMetadata>)>,
     mut auto_nav: bevy::ui::auto_directional_navigation::AutoDirectionalNavigator,
 ) -> Result {
     // On ESC press, clear focus indicator
@@ -312,6 +452,14 @@ fn keyboard_input(
         input_focus_visible.0 = false;
     }
 
+    // The other text fields (e.g. the note editor of the tape) keep their keys
+    if auto_nav
+        .input_focus()
+        .is_some_and(|entity| text_fields.contains(entity))
+    {
+        return Ok(());
+    }
+
     // Handle arrow key navigation (physical keys)
     let nav_direction = if keys.just_pressed(KeyCode::ArrowUp) {
         Some(CompassOctant::North)
@@ -348,19 +496,16 @@ fn keyboard_input(
     if logical_keys.just_pressed(Key::Enter) {
         // If a button is focused, activate it
         if let Some(focused_entity) = auto_nav.input_focus()
-            && let Ok((_, children)) = button_query.get(focused_entity)
-            && let Ok(button_text) = text_query.get(children[0])
+            && let Ok(ButtonAction(button)) = button_query.get(focused_entity).copied()
         {
-            debug!("Activating focused button: {}", button_text.0.as_str());
+            debug!("Activating focused button: {}", button);
 
-            let (mut editable, mut op_metadata) = operation_query.single_mut()?;
-            process_button_action(button_text.0.as_str(), &mut editable, &mut op_metadata)?;
+            actions.write(CalcAction::Press(button));
         } else {
             // No focused button, Enter triggers EQUAL
             debug!("Key pressed: Enter -> button: {}", EQUAL_BUTTON);
 
-            let (mut editable, mut op_metadata) = operation_query.single_mut()?;
-            process_button_action(EQUAL_BUTTON, &mut editable, &mut op_metadata)?;
+            actions.write(CalcAction::Press(EQUAL_BUTTON));
         }
         return Ok(());
     }
@@ -374,74 +519,64 @@ fn on_button_click(
     click: On<Pointer<Click>>,
     mut input_focus: ResMut<InputFocus>,
     mut input_focus_visible: ResMut<InputFocusVisible>,
-    children_query: Query<&Children>,
-    text_query: Query<&Text, Without<OperationMetadata>>,
-    mut operation_query: Query<
-        (&mut EditableText, &mut OperationMetadata),
-        With<OperationMetadata>,
-    >,
+    action_query: Query<&ButtonAction>,
+    mut actions: MessageWriter<CalcAction>,
 ) -> Result {
     let entity = click.entity;
     input_focus_visible.0 = false;
     input_focus.set(entity, FocusCause::Navigated);
 
-    let children = children_query.get(entity)?;
-    let button_text = text_query.get(children[0])?;
+    let ButtonAction(button) = *action_query.get(entity)?;
 
-    debug!("Clicking on button: {}", button_text.0.as_str());
+    debug!("Clicking on button: {}", button);
 
-    let (mut editable, mut op_metadata) = operation_query.single_mut()?;
-    process_button_action(button_text.0.as_str(), &mut editable, &mut op_metadata)?;
+    actions.write(CalcAction::Press(button));
 
     Ok(())
 }
 
 /// Handle the button state (background color, border color)
-#[allow(clippy::type_complexity)]
+#[allow(clippy::type_complexity, clippy::needless_pass_by_value)]
 fn button_state(
     mut interaction_query: Query<
         (
             &Interaction,
             &mut BackgroundColor,
             &mut BorderColor,
-            &Children,
+            &ButtonAction,
+            &ButtonCategory,
         ),
         (Changed<Interaction>, With<CalcButton>),
     >,
-    text_query: Query<&Text, Without<OperationMetadata>>,
     operation_query: Query<&OperationMetadata>,
+    theme: Res<Theme>,
 ) -> Result {
-    for (interaction, mut bg_color, mut border_color, children) in &mut interaction_query {
-        let button_text = text_query.get(children[0])?;
-
-        debug!(
-            "Interaction '{:?}' on button: {}",
-            *interaction,
-            button_text.0.as_str()
-        );
+    for (interaction, mut bg_color, mut border_color, action, category) in &mut interaction_query {
+        debug!("Interaction '{:?}' on button: {}", *interaction, action.0);
 
         let op_metadata = operation_query.single()?;
+        let colors = theme.button_colors(category.0);
 
         match *interaction {
             Interaction::Pressed => {
-                *bg_color = PRESSED_BUTTON.into();
+                *bg_color = colors.pressed.into();
             }
             Interaction::Hovered => {
-                *bg_color = HOVERED_BUTTON.into();
-                *border_color = BorderColor::all(Color::WHITE);
+                *bg_color = colors.hovered.into();
+                *border_color = BorderColor::all(theme.highlight_border);
             }
             Interaction::None => {
                 // Prevent the current operator button to be un-highlighted
                 if let Some(operator) = op_metadata.operator() {
                     let button_variant: ButtonVariant = operator.into();
 
-                    if button_variant != button_text.0.as_str() {
-                        *bg_color = NORMAL_BUTTON.into();
-                        *border_color = BorderColor::all(Color::BLACK);
+                    if button_variant != action.0 {
+                        *bg_color = colors.normal.into();
+                        *border_color = BorderColor::all(theme.border);
                     }
                 } else {
-                    *bg_color = NORMAL_BUTTON.into();
-                    *border_color = BorderColor::all(Color::BLACK);
+                    *bg_color = colors.normal.into();
+                    *border_color = BorderColor::all(theme.border);
                 }
             }
         }
@@ -459,20 +594,21 @@ fn buttons_state(
             &Interaction,
             &mut BackgroundColor,
             &mut BorderColor,
-            &Children,
+            &ButtonAction,
+            &ButtonCategory,
         ),
         With<CalcButton>,
     >,
-    texts_query: Query<&Text, Without<OperationMetadata>>,
     operation_query: Query<&OperationMetadata>,
     focus_helper: IsFocusedHelper,
     input_focus_visible: Res<InputFocusVisible>,
+    theme: Res<Theme>,
 ) -> Result {
     let show_hover = !input_focus_visible.0;
 
-    for (entity, interaction, mut bg_color, mut border_color, children) in &mut buttons {
-        let button_text = texts_query.get(children[0])?;
+    for (entity, interaction, mut bg_color, mut border_color, action, category) in &mut buttons {
         let op_metadata = operation_query.single()?;
+        let colors = theme.button_colors(category.0);
 
         let is_focused = focus_helper.is_focus_visible(entity);
 
@@ -481,27 +617,27 @@ fn buttons_state(
         {
             let button: ButtonVariant = operator.into();
 
-            if button_text.0.as_str() == button {
-                *border_color = BorderColor::all(Color::WHITE);
+            if action.0 == button {
+                *border_color = BorderColor::all(theme.highlight_border);
             } else if is_focused {
-                *bg_color = FOCUSED_BUTTON.into();
-                *border_color = BorderColor::all(Color::srgb(0.3, 0.5, 1.0));
+                *bg_color = theme.focus.into();
+                *border_color = BorderColor::all(theme.focus_ring);
             } else if show_hover && *interaction == Interaction::Hovered {
-                *bg_color = HOVERED_BUTTON.into();
-                *border_color = BorderColor::all(Color::WHITE);
+                *bg_color = colors.hovered.into();
+                *border_color = BorderColor::all(theme.highlight_border);
             } else {
-                *bg_color = NORMAL_BUTTON.into();
-                *border_color = BorderColor::all(Color::BLACK);
+                *bg_color = colors.normal.into();
+                *border_color = BorderColor::all(theme.border);
             }
         } else if is_focused {
-            *bg_color = FOCUSED_BUTTON.into();
-            *border_color = BorderColor::all(Color::srgb(0.3, 0.5, 1.0));
+            *bg_color = theme.focus.into();
+            *border_color = BorderColor::all(theme.focus_ring);
         } else if show_hover && *interaction == Interaction::Hovered {
-            *bg_color = HOVERED_BUTTON.into();
-            *border_color = BorderColor::all(Color::WHITE);
+            *bg_color = colors.hovered.into();
+            *border_color = BorderColor::all(theme.highlight_border);
         } else {
-            *bg_color = NORMAL_BUTTON.into();
-            *border_color = BorderColor::all(Color::BLACK);
+            *bg_color = colors.normal.into();
+            *border_color = BorderColor::all(theme.border);
         }
     }
 
@@ -509,5 +645,21 @@ fn buttons_state(
 }
 
 fn main() {
+    #[cfg(feature = "tui")]
+    if std::env::args().any(|arg| arg == "--tui") {
+        if let Err(err) = tui::run() {
+            eprintln!("Terminal UI failed: {err}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if std::env::args().any(|arg| arg == "--headless")
+        && let Some(path) = config::arg_value("--replay")
+    {
+        std::process::exit(recorder::replay_headless(&path));
+    }
+
     App::new().add_plugins(AppPlugin).run();
 }
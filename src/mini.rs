@@ -0,0 +1,284 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowLevel};
+
+use crate::action::CalcAction;
+use crate::button::{
+    ADD_BUTTON, ButtonAction, ButtonVariant, DIVIDE_BUTTON, EQUAL_BUTTON, MULTIPLY_BUTTON,
+    SUB_BUTTON,
+};
+use crate::layout::{ButtonLayout, LayoutButton};
+use crate::responsive::design_size;
+use crate::title_bar::TITLE_BAR_HEIGHT;
+
+/// Name of the mini mode layout, e.g. recorded in the history.
+pub const MINI_LAYOUT: &str = "mini";
+
+/// Switch between the standard mode and the mini mode.
+#[derive(Message, Clone, Debug)]
+pub struct ToggleMiniMode;
+
+/// The operators, the most recently used first.
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct RecentOperators(pub Vec<ButtonVariant>);
+
+impl Default for RecentOperators {
+    fn default() -> Self {
+        Self(vec![ADD_BUTTON, SUB_BUTTON, MULTIPLY_BUTTON, DIVIDE_BUTTON])
+    }
+}
+
+/// State of the window to restore when leaving the mini mode.
+#[derive(Clone, Debug)]
+struct WindowState {
+    size: Vec2,
+    position: WindowPosition,
+    level: WindowLevel,
+}
+
+/// The standard mode, to restore when leaving the mini mode.
+#[derive(Clone, Debug)]
+struct StandardMode {
+    layout: ButtonLayout,
+    window: Option<WindowState>,
+}
+
+/// The compact mode: the display plus a single row of the most recent operators, in a small window
+/// kept above the other ones.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct MiniMode {
+    /// The standard mode, while in mini mode
+    standard: Option<StandardMode>,
+    /// Last position of the window in mini mode, restored the next time
+    position: Option<IVec2>,
+}
+
+impl MiniMode {
+    pub const fn is_enabled(&self) -> bool {
+        self.standard.is_some()
+    }
+}
+
+/// Layout of the mini mode: the most recent operators, then `=`.
+pub fn mini_layout(recent: &RecentOperators) -> ButtonLayout {
+    let buttons: Vec<LayoutButton> = recent
+        .0
+        .iter()
+        .copied()
+        .chain([EQUAL_BUTTON])
+        .zip(0..)
+        .map(|(action, column)| LayoutButton {
+            action: ButtonAction(action),
+            row: 0,
+            column,
+            row_span: 1,
+            column_span: 1,
+        })
+        .collect();
+
+    ButtonLayout {
+        name: MINI_LAYOUT.to_string(),
+        rows: 1,
+        columns: buttons
+            .iter()
+            .map(|button| button.column + 1)
+            .max()
+            .unwrap_or(1),
+        buttons,
+    }
+}
+
+pub struct MiniModePlugin;
+
+impl Plugin for MiniModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<ToggleMiniMode>();
+        app.init_resource::<MiniMode>();
+        app.init_resource::<RecentOperators>();
+        let keep_layout = keep_mini_layout.after(toggle_mini_mode);
+        #[cfg(not(target_arch = "wasm32"))]
+        let keep_layout = keep_layout.after(crate::layout::reload_layout);
+        app.add_systems(
+            Update,
+            (track_recent_operators, toggle_mini_mode, keep_layout),
+        );
+    }
+}
+
+/// Remember the position of the window in mini mode across runs, in the configuration directory
+/// of the user (not on the Web, where the window doesn't move).
+#[cfg(not(target_arch = "wasm32"))]
+pub struct MiniPositionPlugin;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Plugin for MiniPositionPlugin {
+    fn build(&self, app: &mut App) {
+        let position = position_file()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|text| parse_position(&text));
+
+        app.insert_resource(MiniMode {
+            standard: None,
+            position,
+        });
+        app.add_systems(Update, track_mini_position.after(toggle_mini_mode));
+        app.add_systems(Last, save_mini_position);
+    }
+}
+
+/// File of the position of the window in mini mode, e.g. `~/.config/calculator-gui-rs/mini-position`
+/// on Linux.
+#[cfg(not(target_arch = "wasm32"))]
+fn position_file() -> Option<std::path::PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .or_else(|| std::env::var_os("APPDATA"))
+        .map(std::path::PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| std::path::Path::new(&home).join(".config"))
+        })?;
+
+    Some(
+        config_dir
+            .join(env!("CARGO_PKG_NAME"))
+            .join("mini-position"),
+    )
+}
+
+/// Parse a window position, as saved (e.g. `100 200`).
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parse_position(text: &str) -> Option<IVec2> {
+    let (x, y) = text.trim().split_once(' ')?;
+    Some(IVec2::new(x.parse().ok()?, y.parse().ok()?))
+}
+
+/// Move the pressed operators first.
+fn track_recent_operators(
+    mut actions: MessageReader<CalcAction>,
+    mut recent: ResMut<RecentOperators>,
+) {
+    for action in actions.read() {
+        if let CalcAction::Press(button) = action
+            && let Some(index) = recent.0.iter().position(|operator| operator == button)
+            && index > 0
+        {
+            let operator = recent.0.remove(index);
+            recent.0.insert(0, operator);
+        }
+    }
+}
+
+/// Keep the mini layout while in mini mode: a layout loaded meanwhile (e.g. its file being modified)
+/// becomes the standard one, restored when leaving the mini mode.
+#[allow(clippy::needless_pass_by_value)]
+pub fn keep_mini_layout(
+    mut mini_mode: ResMut<MiniMode>,
+    recent: Res<RecentOperators>,
+    mut layout: ResMut<ButtonLayout>,
+) {
+    if !layout.is_changed() || layout.name == MINI_LAYOUT {
+        return;
+    }
+
+    if let Some(standard) = &mut mini_mode.standard {
+        info!("Keeping the mini mode, over the layout: {}", layout.name);
+
+        standard.layout = std::mem::replace(&mut *layout, mini_layout(&recent));
+    }
+}
+
+/// Follow the position of the window in mini mode.
+#[cfg(not(target_arch = "wasm32"))]
+fn track_mini_position(
+    mut mini_mode: ResMut<MiniMode>,
+    window: Option<Single<&Window, With<PrimaryWindow>>>,
+) {
+    if let Some(window) = window
+        && mini_mode.is_enabled()
+        && let WindowPosition::At(position) = window.position
+        && mini_mode.position != Some(position)
+    {
+        mini_mode.position = Some(position);
+    }
+}
+
+/// Save the position of the window in mini mode when the app exits, for the next runs.
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(clippy::needless_pass_by_value)]
+fn save_mini_position(mut exit: MessageReader<AppExit>, mini_mode: Res<MiniMode>) {
+    if exit.read().next().is_none() {
+        return;
+    }
+    let (Some(path), Some(position)) = (position_file(), mini_mode.position) else {
+        return;
+    };
+
+    let saved = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, format!("{} {}", position.x, position.y)));
+    if let Err(err) = saved {
+        warn!(
+            "Failed to save the mini mode position to {}: {err}",
+            path.display()
+        );
+    }
+}
+
+/// Enter or leave the mini mode, swapping the layout and the window state.
+#[allow(clippy::needless_pass_by_value)]
+fn toggle_mini_mode(
+    mut toggles: MessageReader<ToggleMiniMode>,
+    mut mini_mode: ResMut<MiniMode>,
+    recent: Res<RecentOperators>,
+    mut layout: ResMut<ButtonLayout>,
+    window: Option<Single<&mut Window, With<PrimaryWindow>>>,
+) {
+    if toggles.read().count() == 0 {
+        return;
+    }
+    let mut window = window.map(Single::into_inner);
+
+    if let Some(standard) = mini_mode.standard.take() {
+        info!("Leaving the mini mode");
+
+        if let (Some(window), Some(state)) = (&mut window, standard.window) {
+            if let WindowPosition::At(position) = window.position {
+                mini_mode.position = Some(position);
+            }
+            window.resolution.set(state.size.x, state.size.y);
+            window.position = state.position;
+            window.window_level = state.level;
+        }
+        *layout = standard.layout;
+    } else {
+        info!("Entering the mini mode");
+
+        let mini = mini_layout(&recent);
+        let state = window.as_mut().map(|window| {
+            let state = WindowState {
+                size: window.resolution.size(),
+                position: window.position,
+                level: window.window_level,
+            };
+
+            let size = design_size(mini.rows + 1) + Vec2::Y * TITLE_BAR_HEIGHT;
+            window.resolution.set(size.x, size.y);
+            if let Some(position) = mini_mode.position {
+                window.position = WindowPosition::At(position);
+            }
+            window.window_level = WindowLevel::AlwaysOnTop;
+
+            state
+        });
+        mini_mode.standard = Some(StandardMode {
+            layout: std::mem::replace(&mut *layout, mini),
+            window: state,
+        });
+    }
+}
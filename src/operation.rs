@@ -9,9 +9,10 @@ use bevy::prelude::*;
 use std::fmt;
 
 use crate::button::{ADD_BUTTON, ButtonVariant, DIVIDE_BUTTON, MULTIPLY_BUTTON, SUB_BUTTON};
+use crate::format::NumberFormat;
 
 /// All possible operators for the calculator.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum CalcOperator {
     Add,
     Sub,
@@ -30,6 +31,18 @@ impl From<CalcOperator> for ButtonVariant {
     }
 }
 
+impl CalcOperator {
+    /// Symbol shown to the user, e.g. `×` for a multiplication.
+    pub const fn symbol(self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Sub => '-',
+            Self::Mul => '×',
+            Self::Div => '÷',
+        }
+    }
+}
+
 impl fmt::Display for CalcOperator {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -42,32 +55,68 @@ impl fmt::Display for CalcOperator {
 }
 
 /// Represents and manage the ongoing operation.
-#[derive(Default, Component, Clone)]
+#[derive(Default, Component, Clone, Debug, PartialEq)]
 pub struct OperationMetadata {
     left_operand: Option<f64>,
     right_operand: Option<f64>,
     operator: Option<CalcOperator>,
+    /// Text written on the display by the calculator (e.g. a computed value), with its value at
+    /// full precision
+    shown: Option<(String, f64)>,
 }
 
 impl OperationMetadata {
-    pub fn set_operand(&mut self, operand: &str) -> Result {
+    pub const fn set_operand(&mut self, operand: f64) {
         if self.is_under_operation() {
-            self.set_right_operand(operand)
+            self.set_right_operand(operand);
         } else {
-            self.set_left_operand(operand)
+            self.set_left_operand(operand);
         }
     }
 
-    pub fn set_left_operand(&mut self, left_operand: &str) -> Result {
-        self.left_operand = Some(left_operand.parse::<f64>()?);
+    /// Sync the current operand with the display value, if valid.
+    pub fn sync_operand(&mut self, display: &str, format: &NumberFormat) {
+        // Once edited, the display is parsed back
+        if !self.is_shown(display) {
+            self.shown = None;
+        }
 
-        Ok(())
+        if let Ok(value) = self.display_value(display, format) {
+            self.set_operand(value);
+        }
+    }
+
+    /// Remember the text written on the display by the calculator, with its value at full
+    /// precision, to be used as operand rather than the rounded text.
+    pub fn set_shown(&mut self, display: String, value: f64) {
+        self.shown = Some((display, value));
     }
 
-    fn set_right_operand(&mut self, right_operand: &str) -> Result {
-        self.right_operand = Some(right_operand.parse::<f64>()?);
+    /// Whether the display text was written by the calculator, rather than typed.
+    pub fn is_shown(&self, display: &str) -> bool {
+        self.shown_value(display).is_some()
+    }
 
-        Ok(())
+    /// Value of the display text at full precision, if written by the calculator.
+    pub fn shown_value(&self, display: &str) -> Option<f64> {
+        self.shown
+            .as_ref()
+            .filter(|(shown, _)| shown == display)
+            .map(|(_, value)| *value)
+    }
+
+    /// Value of the display text, at full precision if written by the calculator.
+    pub fn display_value(&self, display: &str, format: &NumberFormat) -> Result<f64> {
+        self.shown_value(display)
+            .map_or_else(|| format.parse(display), Ok)
+    }
+
+    pub const fn set_left_operand(&mut self, left_operand: f64) {
+        self.left_operand = Some(left_operand);
+    }
+
+    const fn set_right_operand(&mut self, right_operand: f64) {
+        self.right_operand = Some(right_operand);
     }
 
     pub const fn set_operator(&mut self, operator: CalcOperator) {
@@ -78,6 +127,10 @@ impl OperationMetadata {
         self.left_operand
     }
 
+    pub const fn right_operand(&self) -> Option<f64> {
+        self.right_operand
+    }
+
     pub const fn operator(&self) -> Option<CalcOperator> {
         self.operator
     }
@@ -111,4 +164,37 @@ impl OperationMetadata {
         self.right_operand = None;
         self.operator = None;
     }
+
+    /// Describe the ongoing operation to the user, e.g. `12 ×` while the right operand is typed,
+    /// or `12 × 7` once `complete`. Nothing is described before an operator is chosen.
+    pub fn describe(&self, format: &NumberFormat, complete: bool) -> String {
+        let (Some(left_operand), Some(operator)) = (self.left_operand, self.operator) else {
+            return String::new();
+        };
+
+        let mut description = format!("{} {}", format.format(left_operand), operator.symbol());
+        if complete && let Some(right_operand) = self.right_operand {
+            description.push(' ');
+            description.push_str(&format.format(right_operand));
+        }
+
+        description
+    }
+}
+
+impl fmt::Display for OperationMetadata {
+    /// Write the ongoing operation, as far as it is known (e.g. `12 * 7`, `12 *` or `12`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(left_operand) = self.left_operand {
+            write!(f, "{left_operand}")?;
+        }
+        if let Some(operator) = self.operator {
+            write!(f, " {operator}")?;
+        }
+        if let (Some(_), Some(right_operand)) = (self.operator, self.right_operand) {
+            write!(f, " {right_operand}")?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,386 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::text::{EditableText, TextEditChange};
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::str::FromStr;
+
+use crate::CalculatorPlugin;
+use crate::action::{CalcAction, apply_calc_actions, reset_editable};
+use crate::button;
+use crate::config::arg_value;
+use crate::format::NumberFormat;
+use crate::locale::Locale;
+use crate::operation::OperationMetadata;
+
+/// Number of frames to run after an input, so the resulting action is applied and its display edit synced back.
+pub const SETTLE_FRAMES: usize = 3;
+
+/// First line of an input log file.
+const HEADER: &str = "# calculator-gui-rs input log";
+
+/// Record the calculator inputs to a file with `--record <path>`, or replay them with `--replay <path>`.
+///
+/// The input log is a plain text file, with one tab-separated `<seconds> <kind> <value>` line per input:
+/// `press` for the buttons clicked or activated with the keyboard, `evaluate` for the evaluated
+/// expressions, `edit` for the display edits, and a final `end` line with the display value.
+/// It starts with a `format` line holding the display format settings (e.g. the locale), applied
+/// before replaying so the display is the same on another machine, and written again whenever
+/// they change.
+pub struct RecorderPlugin;
+
+impl Plugin for RecorderPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = arg_value("--record") {
+            match File::create(&path) {
+                Ok(file) => {
+                    let mut writer = BufWriter::new(file);
+                    let format = app.world().resource::<NumberFormat>();
+                    let format = LogEntry {
+                        time: 0.,
+                        input: Input::Format(format_settings(format)),
+                    };
+                    if let Err(err) = writeln!(writer, "{HEADER}\n{format}") {
+                        error!("Failed to write the input log '{path}': {err}");
+                    }
+
+                    info!("Recording the inputs to '{path}'");
+
+                    app.insert_resource(Recording { writer });
+                    app.add_systems(Update, (record_actions, record_format));
+                    app.add_systems(Last, finish_recording);
+                    app.add_observer(record_display_edit);
+                }
+                Err(err) => error!("Failed to create the input log '{path}': {err}"),
+            }
+        }
+
+        if let Some(path) = arg_value("--replay") {
+            let replay = Replay::load(&path, true).and_then(|mut replay| {
+                replay
+                    .apply_recorded_format(&mut app.world_mut().resource_mut::<NumberFormat>())?;
+                Ok(replay)
+            });
+            match replay {
+                Ok(replay) => {
+                    info!("Replaying the inputs from '{path}'");
+
+                    app.insert_resource(replay);
+                    app.add_systems(Update, replay_inputs.before(apply_calc_actions));
+                }
+                Err(err) => error!("Failed to load the input log '{path}': {err}"),
+            }
+        }
+    }
+}
+
+/// Replay an input log without any window, returning the process exit code: `0` if the final
+/// display matches the recorded one, `1` if it doesn't or if the log has no final display.
+pub fn replay_headless(path: &str) -> i32 {
+    let mut app = CalculatorPlugin::headless_app();
+    let replay = Replay::load(path, false).and_then(|mut replay| {
+        replay.apply_recorded_format(&mut app.world_mut().resource_mut::<NumberFormat>())?;
+        Ok(replay)
+    });
+    let replay = match replay {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("Failed to load the input log '{path}': {err}");
+            return 2;
+        }
+    };
+
+    app.insert_resource(replay);
+    app.add_systems(Update, replay_inputs.before(apply_calc_actions));
+
+    if app.run().is_success() { 0 } else { 1 }
+}
+
+/// A recorded input.
+#[derive(Clone, Debug, PartialEq)]
+enum Input {
+    Press(String),
+    Evaluate(String),
+    Edit(String),
+    End(String),
+    /// Display format settings, see [`format_settings`]
+    Format(String),
+}
+
+/// A line of the input log.
+#[derive(Clone, Debug, PartialEq)]
+struct LogEntry {
+    /// Seconds since the app started
+    time: f64,
+    input: Input,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, value) = match &self.input {
+            Input::Press(value) => ("press", value),
+            Input::Evaluate(value) => ("evaluate", value),
+            Input::Edit(value) => ("edit", value),
+            Input::End(value) => ("end", value),
+            Input::Format(value) => ("format", value),
+        };
+
+        write!(f, "{:.3}\t{kind}\t{value}", self.time)
+    }
+}
+
+impl FromStr for LogEntry {
+    type Err = BevyError;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let mut fields = line.splitn(3, '\t');
+        let (Some(time), Some(kind), Some(value)) = (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(format!("Malformed input log line: '{line}'").into());
+        };
+
+        let value = value.to_string();
+        let input = match kind {
+            "press" => Input::Press(value),
+            "evaluate" => Input::Evaluate(value),
+            "edit" => Input::Edit(value),
+            "end" => Input::End(value),
+            "format" => Input::Format(value),
+            _ => return Err(format!("Unknown input kind: '{kind}'").into()),
+        };
+
+        Ok(Self {
+            time: time.parse()?,
+            input,
+        })
+    }
+}
+
+#[derive(Resource)]
+struct Recording {
+    writer: BufWriter<File>,
+}
+
+impl Recording {
+    fn write(&mut self, time: &Time<Real>, input: Input) {
+        let entry = LogEntry {
+            time: time.elapsed_secs_f64(),
+            input,
+        };
+
+        // Flush every line, so the log is usable even if the app crashes
+        if let Err(err) = writeln!(self.writer, "{entry}").and_then(|()| self.writer.flush()) {
+            error!("Failed to write the input log: {err}");
+        }
+    }
+}
+
+/// Record the calculator actions (clicks, keyboard, ...).
+#[allow(clippy::needless_pass_by_value)]
+fn record_actions(
+    mut actions: MessageReader<CalcAction>,
+    mut recording: ResMut<Recording>,
+    time: Res<Time<Real>>,
+) {
+    for action in actions.read() {
+        let input = match action {
+            CalcAction::Press(button) => Input::Press((*button).to_string()),
+            CalcAction::Evaluate(expression) => Input::Evaluate(expression.clone()),
+        };
+
+        recording.write(&time, input);
+    }
+}
+
+/// Record the display format settings changed during the session (e.g. in the settings panel).
+#[allow(clippy::needless_pass_by_value)]
+fn record_format(
+    format: Res<NumberFormat>,
+    mut recording: ResMut<Recording>,
+    time: Res<Time<Real>>,
+) {
+    // The initial settings are written with the header
+    if format.is_changed() && !format.is_added() {
+        recording.write(&time, Input::Format(format_settings(&format)));
+    }
+}
+
+/// Record the display edits, including the ones typed directly in the display.
+#[allow(clippy::needless_pass_by_value)]
+fn record_display_edit(
+    _change: On<TextEditChange>,
+    display: Single<&EditableText, With<OperationMetadata>>,
+    mut recording: ResMut<Recording>,
+    time: Res<Time<Real>>,
+) {
+    recording.write(&time, Input::Edit(display.value().to_string()));
+}
+
+/// Record the final display value when the app exits.
+#[allow(clippy::needless_pass_by_value)]
+fn finish_recording(
+    mut exit: MessageReader<AppExit>,
+    display: Single<&EditableText, With<OperationMetadata>>,
+    mut recording: ResMut<Recording>,
+    time: Res<Time<Real>>,
+) {
+    if exit.read().next().is_some() {
+        recording.write(&time, Input::End(display.value().to_string()));
+    }
+}
+
+#[derive(Resource)]
+struct Replay {
+    entries: VecDeque<LogEntry>,
+    /// Whether the inputs are replayed at their recorded time, or as fast as possible
+    paced: bool,
+    /// Frames to wait for the previous input to be applied
+    wait_frames: usize,
+}
+
+impl Replay {
+    fn load(path: &str, paced: bool) -> Result<Self> {
+        let entries = fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::parse)
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            entries,
+            paced,
+            wait_frames: 0,
+        })
+    }
+
+    /// Apply the display format settings the inputs were recorded with, before replaying them.
+    fn apply_recorded_format(&mut self, format: &mut NumberFormat) -> Result {
+        while let Some(LogEntry {
+            input: Input::Format(settings),
+            ..
+        }) = self.entries.front()
+        {
+            apply_format_settings(settings, format)?;
+            self.entries.pop_front();
+        }
+
+        Ok(())
+    }
+}
+
+/// The display format settings changing the display of the inputs, as space-separated
+/// `<name>=<value>` pairs, e.g. `locale=fr-FR precision=2 notation=fix2 rounding=half-even
+/// input-length=16`.
+fn format_settings(format: &NumberFormat) -> String {
+    let precision = format
+        .precision
+        .map_or_else(|| "off".to_string(), |decimals| decimals.to_string());
+
+    format!(
+        "locale={} precision={precision} notation={} rounding={} input-length={}",
+        format.locale, format.notation, format.rounding, format.max_input_length
+    )
+}
+
+/// Apply display format settings written by [`format_settings`].
+fn apply_format_settings(settings: &str, format: &mut NumberFormat) -> Result {
+    for setting in settings.split_whitespace() {
+        let (name, value) = setting
+            .split_once('=')
+            .ok_or_else(|| format!("Malformed format setting: '{setting}'"))?;
+        match name {
+            "locale" => {
+                format.locale =
+                    Locale::from_tag(value).ok_or_else(|| format!("Unknown locale: '{value}'"))?;
+            }
+            "precision" => {
+                format.precision = if value == "off" {
+                    None
+                } else {
+                    Some(value.parse()?)
+                };
+            }
+            "notation" => format.notation = value.parse()?,
+            "rounding" => format.rounding = value.parse()?,
+            "input-length" => format.max_input_length = value.parse()?,
+            _ => return Err(format!("Unknown format setting: '{name}'").into()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Feed the recorded inputs back, one at a time, then compare the final display with the recorded one.
+#[allow(clippy::needless_pass_by_value)]
+fn replay_inputs(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    mut actions: MessageWriter<CalcAction>,
+    mut display: Single<&mut EditableText, With<OperationMetadata>>,
+    mut format: ResMut<NumberFormat>,
+    mut exit: MessageWriter<AppExit>,
+    time: Res<Time<Real>>,
+) -> Result {
+    if replay.wait_frames > 0 {
+        replay.wait_frames -= 1;
+        return Ok(());
+    }
+
+    let Some(entry) = replay.entries.front() else {
+        // A truncated log can't be checked, which fails the headless replays
+        error!("Replay finished, without any recorded final display to compare with");
+        if !replay.paced {
+            exit.write(AppExit::from_code(1));
+        }
+        commands.remove_resource::<Replay>();
+        return Ok(());
+    };
+    if replay.paced && entry.time > time.elapsed_secs_f64() {
+        return Ok(());
+    }
+
+    let entry = replay.entries.pop_front().ok_or("Empty replay")?;
+    match entry.input {
+        Input::Press(label) => {
+            let button = button::from_label(&label).ok_or("Unknown button in the input log")?;
+            actions.write(CalcAction::Press(button));
+        }
+        Input::Evaluate(expression) => {
+            actions.write(CalcAction::Evaluate(expression));
+        }
+        // Most edits are the result of the previous action, and are already applied
+        Input::Edit(text) if display.value().to_string() != text => {
+            reset_editable(&mut display, &text);
+        }
+        Input::Edit(_) => {}
+        Input::Format(settings) => apply_format_settings(&settings, &mut format)?,
+        Input::End(expected) => {
+            let actual = display.value().to_string();
+            let app_exit = if actual == expected {
+                info!("Replay finished, the display matches the recording: {actual}");
+                AppExit::Success
+            } else {
+                error!("Replay finished, the display is '{actual}' instead of '{expected}'");
+                AppExit::from_code(1)
+            };
+
+            // Only exit headless replays, the user may keep using the app otherwise
+            if !replay.paced {
+                exit.write(app_exit);
+            }
+            commands.remove_resource::<Replay>();
+        }
+    }
+
+    replay.wait_frames = SETTLE_FRAMES;
+
+    Ok(())
+}
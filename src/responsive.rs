@@ -0,0 +1,97 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::history::HistoryPanel;
+use crate::layout::ButtonLayout;
+
+/// Size the UI is designed for, at a scale of 1 (the default window size).
+pub const DESIGN_SIZE: Vec2 = Vec2::new(330., 315.);
+/// Rows of the grid fitting in the design size, including the display row.
+const DESIGN_GRID_ROWS: f32 = 6.;
+/// Smallest scale of the UI, whatever the window size.
+const MIN_SCALE: f32 = 0.5;
+/// How much wider than the designed proportions the window must be to show the history panel.
+const WIDE_RATIO: f32 = 1.4;
+/// Part of the window width taken by the history panel, when shown.
+const HISTORY_WIDTH_RATIO: f32 = 0.4;
+
+/// How the UI fills a window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WindowFit {
+    /// Scale of the UI sizes (fonts, borders, etc.)
+    pub scale: f32,
+    /// Whether the history panel is shown beside the buttons
+    pub wide: bool,
+}
+
+/// Size the UI is designed for, at a scale of 1, given the rows of the grid (the display row included).
+pub fn design_size(grid_rows: u16) -> Vec2 {
+    Vec2::new(
+        DESIGN_SIZE.x,
+        DESIGN_SIZE.y / DESIGN_GRID_ROWS * f32::from(grid_rows),
+    )
+}
+
+impl WindowFit {
+    /// Fit the UI in a window of the given (logical) size, given the rows of the grid (the display
+    /// row included).
+    pub fn new(size: Vec2, grid_rows: u16) -> Self {
+        let design = design_size(grid_rows);
+        let wide = size.x / design.x >= WIDE_RATIO * size.y / design.y;
+        let calculator_width = if wide {
+            size.x * (1. - HISTORY_WIDTH_RATIO)
+        } else {
+            size.x
+        };
+        let scale = (calculator_width / design.x)
+            .min(size.y / design.y)
+            .max(MIN_SCALE);
+
+        Self { scale, wide }
+    }
+}
+
+/// Scale the UI with the window, and show the history panel on wide windows.
+pub struct ResponsivePlugin;
+
+impl Plugin for ResponsivePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, fit_window);
+    }
+}
+
+/// Fit the UI in the window, when opened or resized, or when the layout changes.
+#[allow(clippy::needless_pass_by_value)]
+fn fit_window(
+    window: Single<Ref<Window>, With<PrimaryWindow>>,
+    layout: Res<ButtonLayout>,
+    mut ui_scale: ResMut<UiScale>,
+    history_panel: Single<(Ref<HistoryPanel>, &mut Node)>,
+) {
+    let (panel, mut history_panel) = history_panel.into_inner();
+    if !window.is_changed() && !layout.is_changed() && !panel.is_added() {
+        return;
+    }
+
+    let fit = WindowFit::new(window.size(), layout.rows + 1);
+
+    if (ui_scale.0 - fit.scale).abs() > f32::EPSILON {
+        ui_scale.0 = fit.scale;
+    }
+
+    let display = if fit.wide {
+        Display::Flex
+    } else {
+        Display::None
+    };
+    if history_panel.display != display {
+        history_panel.display = display;
+    }
+}
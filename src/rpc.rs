@@ -0,0 +1,329 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::text::EditableText;
+use serde::{Deserialize, Deserializer};
+use serde_json::{Value, json};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use crate::action::{CalcAction, CalcResult, apply_calc_actions};
+use crate::button;
+use crate::config::arg_value;
+use crate::expression;
+use crate::format::NumberFormat;
+use crate::operation::OperationMetadata;
+
+/// Endpoint used when none is given with `--rpc`.
+const DEFAULT_ENDPOINT: &str = "tcp:127.0.0.1:7878";
+
+/// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const CALCULATOR_ERROR: i64 = -32000;
+
+/// Local JSON-RPC 2.0 server to automate the calculator.
+///
+/// Requests and responses are newline-delimited JSON objects, exchanged over the endpoint given
+/// with `--rpc tcp:<address>` or `--rpc unix:<path>` (default: `tcp:127.0.0.1:7878`).
+///
+/// Methods:
+/// - `press` (`button`): press a button given its label, e.g. `7`, `+` or `=`
+/// - `type` (`text`): press the buttons matching each character, e.g. `12*7=`
+/// - `evaluate` (`expression`): evaluate an expression, show and return its result (rounded to the
+///   precision, as shown)
+/// - `display`: read the display value, once the pending actions are applied
+/// - `subscribe`: receive a `result` notification (`expression`, `value`) for every computed result
+///
+/// The requests without `id` are notifications, which are applied without any response.
+pub struct RpcPlugin;
+
+impl Plugin for RpcPlugin {
+    fn build(&self, app: &mut App) {
+        let endpoint = arg_value("--rpc").unwrap_or_else(|| DEFAULT_ENDPOINT.to_string());
+
+        let (sender, receiver) = mpsc::channel();
+        if let Err(err) = start_server(&endpoint, sender) {
+            error!("Failed to start the JSON-RPC server on '{endpoint}': {err}");
+            return;
+        }
+
+        info!("JSON-RPC server listening on '{endpoint}'");
+
+        app.insert_resource(RpcRequests(Mutex::new(receiver)));
+        app.init_resource::<RpcSubscribers>();
+        app.init_resource::<RpcDisplayRequests>();
+        app.add_systems(
+            Update,
+            (
+                handle_rpc_requests.before(apply_calc_actions),
+                (answer_display_requests, notify_rpc_subscribers).after(apply_calc_actions),
+            ),
+        );
+    }
+}
+
+/// A request received by the server, with the channel to reply on its connection.
+struct RpcRequest {
+    /// Identifier of the request, none for a notification
+    id: Option<Value>,
+    method: String,
+    params: Value,
+    connection: Sender<String>,
+}
+
+/// Deserialize a field which may be `null`, as opposed to missing.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+struct RawRequest {
+    #[serde(default, deserialize_with = "present")]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Resource)]
+struct RpcRequests(Mutex<Receiver<RpcRequest>>);
+
+/// Connections which subscribed to the results.
+#[derive(Resource, Default)]
+struct RpcSubscribers(Vec<Sender<String>>);
+
+/// Requests of the display value, answered once the actions requested before are applied.
+#[derive(Resource, Default)]
+struct RpcDisplayRequests(Vec<(Value, Sender<String>)>);
+
+fn start_server(endpoint: &str, requests: Sender<RpcRequest>) -> io::Result<()> {
+    if let Some(address) = endpoint.strip_prefix("tcp:") {
+        let listener = TcpListener::bind(address)?;
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Ok(writer) = stream.try_clone() {
+                    serve_connection(stream, writer, requests.clone());
+                }
+            }
+        });
+
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    if let Some(path) = endpoint.strip_prefix("unix:") {
+        use std::os::unix::fs::FileTypeExt;
+
+        // Remove the socket left over by a previous run, but never any other file
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.file_type().is_socket() => std::fs::remove_file(path)?,
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("'{path}' exists and isn't a socket"),
+                ));
+            }
+            Err(_) => {}
+        }
+
+        let listener = std::os::unix::net::UnixListener::bind(path)?;
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Ok(writer) = stream.try_clone() {
+                    serve_connection(stream, writer, requests.clone());
+                }
+            }
+        });
+
+        return Ok(());
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "expected 'tcp:<address>' or 'unix:<path>'",
+    ))
+}
+
+/// Read the requests of a connection, and write back the responses and notifications, each on their own thread.
+fn serve_connection<R, W>(reader: R, mut writer: W, requests: Sender<RpcRequest>)
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    let (connection, outgoing) = mpsc::channel::<String>();
+
+    thread::spawn(move || {
+        for message in outgoing {
+            if writeln!(writer, "{message}").is_err() {
+                break;
+            }
+        }
+    });
+
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str::<RawRequest>(&line) {
+                Ok(request) => {
+                    let request = RpcRequest {
+                        id: request.id,
+                        method: request.method,
+                        params: request.params,
+                        connection: connection.clone(),
+                    };
+                    if requests.send(request).is_err() {
+                        break;
+                    }
+                }
+                Err(err) => {
+                    let response = error_response(&Value::Null, PARSE_ERROR, &err.to_string());
+                    let _ = connection.send(response);
+                }
+            }
+        }
+    });
+}
+
+/// Translate the received requests into calculator actions.
+#[allow(clippy::needless_pass_by_value)]
+fn handle_rpc_requests(
+    requests: Res<RpcRequests>,
+    mut subscribers: ResMut<RpcSubscribers>,
+    mut display_requests: ResMut<RpcDisplayRequests>,
+    mut actions: MessageWriter<CalcAction>,
+    format: Res<NumberFormat>,
+) -> Result {
+    let requests = requests
+        .0
+        .lock()
+        .map_err(|_| "JSON-RPC requests lock poisoned")?;
+
+    for request in requests.try_iter() {
+        if request.method == "display" {
+            if let Some(id) = request.id {
+                display_requests.0.push((id, request.connection));
+            }
+            continue;
+        }
+
+        let response = call_method(&request, &mut actions, &mut subscribers, &format);
+        // No response to the notifications, even on errors
+        let Some(id) = &request.id else {
+            continue;
+        };
+        let response = match response {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+            Err((code, message)) => error_response(id, code, &message),
+        };
+        let _ = request.connection.send(response);
+    }
+
+    Ok(())
+}
+
+/// Call a method of the server, returning its result or its error code and message.
+fn call_method(
+    request: &RpcRequest,
+    actions: &mut MessageWriter<CalcAction>,
+    subscribers: &mut RpcSubscribers,
+    format: &NumberFormat,
+) -> Result<Value, (i64, String)> {
+    let param = |name: &str| {
+        request
+            .params
+            .get(name)
+            .or_else(|| request.params.get(0))
+            .and_then(Value::as_str)
+            .ok_or_else(|| (INVALID_PARAMS, format!("missing '{name}' parameter")))
+    };
+
+    match request.method.as_str() {
+        "press" => {
+            let label = param("button")?;
+            let button = button::from_label(label)
+                .ok_or_else(|| (CALCULATOR_ERROR, format!("unknown button '{label}'")))?;
+
+            actions.write(CalcAction::Press(button));
+            Ok(Value::Bool(true))
+        }
+        "type" => {
+            let buttons = param("text")?
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| {
+                    button::from_char(c).ok_or((CALCULATOR_ERROR, format!("no button for '{c}'")))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let count = buttons.len();
+            actions.write_batch(buttons.into_iter().map(CalcAction::Press));
+            Ok(json!(count))
+        }
+        "evaluate" => {
+            let expression = param("expression")?;
+            let value = expression::evaluate(expression)
+                .map_err(|err| (CALCULATOR_ERROR, err.to_string()))?;
+
+            // Rounded as the display, see `CalcAction::Evaluate`
+            actions.write(CalcAction::Evaluate(expression.to_string()));
+            Ok(json!(format.round(value)))
+        }
+        "subscribe" => {
+            subscribers.0.push(request.connection.clone());
+            Ok(Value::Bool(true))
+        }
+        method => Err((METHOD_NOT_FOUND, format!("unknown method '{method}'"))),
+    }
+}
+
+/// Answer the requests of the display value, the requested actions being applied.
+fn answer_display_requests(
+    mut display_requests: ResMut<RpcDisplayRequests>,
+    display: Single<&EditableText, With<OperationMetadata>>,
+) {
+    let display = display.value().to_string();
+    for (id, connection) in display_requests.0.drain(..) {
+        let response = json!({ "jsonrpc": "2.0", "id": id, "result": display }).to_string();
+        let _ = connection.send(response);
+    }
+}
+
+/// Send a `result` notification to the subscribers for every computed result.
+fn notify_rpc_subscribers(
+    mut results: MessageReader<CalcResult>,
+    mut subscribers: ResMut<RpcSubscribers>,
+) {
+    for CalcResult(evaluation) in results.read() {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "result",
+            "params": { "expression": evaluation.expression, "value": evaluation.value },
+        })
+        .to_string();
+
+        // Drop the subscribers whose connection is closed
+        subscribers
+            .0
+            .retain(|subscriber| subscriber.send(notification.clone()).is_ok());
+    }
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }).to_string()
+}
@@ -0,0 +1,525 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+use crate::click::ClickFeedback;
+use crate::format::{Notation, NumberFormat, RoundingMode};
+use crate::localization::{Localization, Localized, UiLanguage};
+use crate::theme::{Theme, ThemeRole};
+
+/// Maximum number of decimals selectable for the precision.
+const MAX_PRECISION: u8 = 10;
+/// Maximum input lengths selectable, in digits.
+const INPUT_LENGTHS: [usize; 4] = [8, 12, 16, 32];
+
+/// A user-configurable display setting.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Setting {
+    /// Number of decimals the computed values are rounded to
+    #[default]
+    Precision,
+    Rounding,
+    Notation,
+    /// Maximum number of digits typed on the display
+    InputLength,
+    /// Locale of the numbers, the language of the UI being a separate setting (see [`UiLanguage`])
+    Locale,
+}
+
+impl Setting {
+    pub const ALL: [Self; 5] = [
+        Self::Precision,
+        Self::Rounding,
+        Self::Notation,
+        Self::InputLength,
+        Self::Locale,
+    ];
+
+    /// Identifier of the message labelling the setting, in the translations.
+    pub const fn message_id(self) -> &'static str {
+        match self {
+            Self::Precision => "setting-precision",
+            Self::Rounding => "setting-rounding",
+            Self::Notation => "setting-notation",
+            Self::InputLength => "setting-input-length",
+            Self::Locale => "setting-locale",
+        }
+    }
+
+    /// Current value of the setting, in the current language.
+    pub fn value(self, format: &NumberFormat, localization: &Localization) -> String {
+        match self {
+            Self::Precision => format.precision.map_or_else(
+                || localization.text("setting-off"),
+                |decimals| decimals.to_string(),
+            ),
+            Self::Rounding => rounding_name(format.rounding, localization),
+            Self::Notation => notation_name(format.notation, localization),
+            Self::InputLength => format.max_input_length.to_string(),
+            Self::Locale => format.locale.to_string(),
+        }
+    }
+
+    /// Switch the setting to its next value, wrapping around.
+    pub fn cycle(self, format: &mut NumberFormat) {
+        match self {
+            Self::Precision => {
+                format.precision = match format.precision {
+                    None => Some(0),
+                    Some(decimals) if decimals < MAX_PRECISION => Some(decimals + 1),
+                    Some(_) => None,
+                };
+            }
+            Self::Rounding => format.rounding = format.rounding.next(),
+            Self::Notation => format.notation = format.notation.next(),
+            Self::InputLength => {
+                format.max_input_length = INPUT_LENGTHS
+                    .into_iter()
+                    .find(|length| *length > format.max_input_length)
+                    .unwrap_or(INPUT_LENGTHS[0]);
+            }
+            Self::Locale => format.locale = format.locale.next(),
+        }
+    }
+}
+
+/// The settings panel, shown over the buttons.
+#[derive(Component, Default, Clone)]
+pub struct SettingsPanel;
+
+/// Summary of the settings, shown on the display.
+#[derive(Component, Default, Clone)]
+pub struct SettingsIndicator;
+
+/// A row of the settings panel, switching its setting to the next value when clicked.
+#[derive(Component, Default, Clone, Copy)]
+pub struct SettingRow(pub Setting);
+
+/// The row of the settings panel switching to the next language of the UI when clicked.
+#[derive(Component, Default, Clone)]
+pub struct LanguageRow;
+
+/// The row of the settings panel switching to the next built-in theme when clicked.
+#[derive(Component, Default, Clone)]
+pub struct ThemeRow;
+
+/// A key click setting (see [`crate::click`]), shown in the settings panel.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClickSetting {
+    /// Whether the key clicks are played
+    #[default]
+    Sound,
+    Volume,
+    /// Whether the device vibrates on the key presses (on mobile browsers)
+    Vibration,
+}
+
+impl ClickSetting {
+    pub const ALL: [Self; 3] = [Self::Sound, Self::Volume, Self::Vibration];
+
+    /// Identifier of the message labelling the row, in the translations.
+    pub const fn message_id(self) -> &'static str {
+        match self {
+            Self::Sound => "setting-clicks",
+            Self::Volume => "setting-volume",
+            Self::Vibration => "setting-vibration",
+        }
+    }
+
+    /// Current value of the setting.
+    pub fn value(self, feedback: &ClickFeedback, localization: &Localization) -> String {
+        match self {
+            Self::Sound if feedback.muted => localization.text("setting-off"),
+            Self::Sound => localization.text("setting-on"),
+            Self::Volume => format!("{:.0}%", feedback.volume * 100.),
+            Self::Vibration if feedback.vibration => localization.text("setting-on"),
+            Self::Vibration => localization.text("setting-off"),
+        }
+    }
+
+    /// Switch the setting to its next value, wrapping around.
+    pub fn cycle(self, feedback: &mut ClickFeedback) {
+        match self {
+            Self::Sound => feedback.muted = !feedback.muted,
+            Self::Volume => feedback.next_volume(),
+            Self::Vibration => feedback.vibration = !feedback.vibration,
+        }
+    }
+}
+
+/// A row of the settings panel for a key click setting, switching it when clicked.
+#[derive(Component, Default, Clone, Copy)]
+pub struct ClickRow(pub ClickSetting);
+
+/// Text showing the value of a setting, in the settings panel.
+#[derive(Component, Default, Clone)]
+struct SettingValue;
+
+/// The settings panel (precision, rounding mode, notation, maximum input length, locale, language,
+/// theme, key clicks), opened by clicking the indicator on the display or with `F2`.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_settings_panel,
+                setting_rows_state,
+                update_setting_values,
+                update_language_value,
+                update_theme_value,
+                update_click_values,
+                update_settings_indicator,
+            ),
+        );
+    }
+}
+
+/// The indicator of the current settings, to be placed on the display.
+pub fn indicator() -> impl Scene {
+    bsn! {
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(4.),
+            left: Val::Percent(8.),
+        }
+        SettingsIndicator
+        Text::default()
+        TextFont::from_font_size(10.)
+        TextColor::default()
+        ThemeRole::SecondaryText
+        on(on_indicator_click)
+    }
+}
+
+/// The settings panel, hidden until opened, to be placed over the buttons.
+pub fn panel() -> impl Scene {
+    let rows: Vec<Box<dyn Scene>> = Setting::ALL
+        .into_iter()
+        .map(|setting| {
+            Box::new(bsn! {
+                row({setting.message_id()})
+                SettingRow({setting})
+                on(on_setting_click)
+            }) as Box<dyn Scene>
+        })
+        .collect();
+    let click_rows: Vec<Box<dyn Scene>> = ClickSetting::ALL
+        .into_iter()
+        .map(|setting| {
+            Box::new(bsn! {
+                row({setting.message_id()})
+                ClickRow({setting})
+                on(on_click_row_click)
+            }) as Box<dyn Scene>
+        })
+        .collect();
+
+    bsn! {
+        Node {
+            display: Display::None,
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(8.),
+            padding: UiRect::all(Val::Px(16.)),
+        }
+        SettingsPanel
+        BackgroundColor::default()
+        ThemeRole::Panel
+        Children [
+            { rows },
+            (
+                row("setting-language")
+                LanguageRow
+                on(on_language_click)
+            ),
+            (
+                row("setting-theme")
+                ThemeRow
+                on(on_theme_click)
+            ),
+            { click_rows },
+        ]
+    }
+}
+
+/// A row of the settings panel, labelled after the identifier of its message and showing the value
+/// of its setting, to be completed with the marker of the setting and its click observer.
+fn row(message_id: &'static str) -> impl Scene {
+    bsn! {
+        Button
+        Node {
+            justify_content: JustifyContent::SpaceBetween,
+            padding: UiRect::axes(Val::Px(12.), Val::Px(8.)),
+            border: UiRect::all(Val::Px(2.)),
+            border_radius: BorderRadius::MAX,
+        }
+        BorderColor::default()
+        BackgroundColor::default()
+        Children [
+            (
+                Localized({message_id})
+                Text::default()
+                TextColor::default()
+                ThemeRole::PanelText
+            ),
+            (
+                SettingValue
+                Text::default()
+                TextColor::default()
+                ThemeRole::PanelText
+            ),
+        ]
+    }
+}
+
+fn set_panel_visible(panel: &mut Node, visible: bool) {
+    panel.display = if visible {
+        Display::Flex
+    } else {
+        Display::None
+    };
+}
+
+/// Toggle the settings panel with `F2`, and close it with `Esc`.
+#[allow(clippy::needless_pass_by_value)]
+fn toggle_settings_panel(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut panel: Single<&mut Node, With<SettingsPanel>>,
+) {
+    if keys.just_pressed(KeyCode::F2) {
+        let visible = panel.display == Display::None;
+        set_panel_visible(&mut panel, visible);
+    } else if keys.just_pressed(KeyCode::Escape) {
+        set_panel_visible(&mut panel, false);
+    }
+}
+
+/// Open or close the settings panel when clicking the indicator.
+fn on_indicator_click(
+    _click: On<Pointer<Click>>,
+    mut panel: Single<&mut Node, With<SettingsPanel>>,
+) {
+    let visible = panel.display == Display::None;
+    set_panel_visible(&mut panel, visible);
+}
+
+/// Switch a setting to its next value when clicking its row.
+#[allow(clippy::needless_pass_by_value)]
+fn on_setting_click(
+    click: On<Pointer<Click>>,
+    rows: Query<&SettingRow>,
+    mut format: ResMut<NumberFormat>,
+    localization: Res<Localization>,
+) -> Result {
+    let SettingRow(setting) = *rows.get(click.entity)?;
+    setting.cycle(&mut format);
+
+    debug!(
+        "Setting {setting:?} to: {}",
+        setting.value(&format, &localization)
+    );
+
+    Ok(())
+}
+
+/// Switch to the next language of the UI when clicking the language row.
+fn on_language_click(_click: On<Pointer<Click>>, mut ui_language: ResMut<UiLanguage>) {
+    *ui_language = ui_language.next();
+
+    debug!("Setting Language to: {:?}", ui_language.0);
+}
+
+/// Switch to the next built-in theme when clicking the theme row.
+fn on_theme_click(_click: On<Pointer<Click>>, mut theme: ResMut<Theme>) {
+    *theme = theme.next();
+
+    debug!("Setting Theme to: {}", theme.name);
+}
+
+/// Switch a key click setting when clicking its row.
+#[allow(clippy::needless_pass_by_value)]
+fn on_click_row_click(
+    click: On<Pointer<Click>>,
+    rows: Query<&ClickRow>,
+    mut feedback: ResMut<ClickFeedback>,
+) -> Result {
+    let ClickRow(setting) = *rows.get(click.entity)?;
+    setting.cycle(&mut feedback);
+
+    debug!("Setting {setting:?} to: {feedback:?}");
+
+    Ok(())
+}
+
+/// Highlight the hovered setting rows.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn setting_rows_state(
+    mut rows: Query<
+        (Ref<Interaction>, &mut BackgroundColor, &mut BorderColor),
+        Or<(
+            With<SettingRow>,
+            With<LanguageRow>,
+            With<ThemeRow>,
+            With<ClickRow>,
+        )>,
+    >,
+    theme: Res<Theme>,
+) {
+    for (interaction, mut bg_color, mut border_color) in &mut rows {
+        if !theme.is_changed() && !interaction.is_changed() {
+            continue;
+        }
+
+        *bg_color = match *interaction {
+            Interaction::None => theme.function.normal.into(),
+            Interaction::Hovered | Interaction::Pressed => theme.function.hovered.into(),
+        };
+        *border_color = BorderColor::all(theme.border);
+    }
+}
+
+/// Show the current settings in the panel, when spawned or when changed.
+#[allow(clippy::needless_pass_by_value)]
+fn update_setting_values(
+    format: Res<NumberFormat>,
+    localization: Res<Localization>,
+    rows: Query<(Ref<SettingRow>, &Children)>,
+    mut texts: Query<&mut Text, With<SettingValue>>,
+) {
+    for (row, children) in &rows {
+        if !format.is_changed() && !localization.is_changed() && !row.is_added() {
+            continue;
+        }
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.0 = row.0.value(&format, &localization);
+            }
+        }
+    }
+}
+
+/// Show the language of the UI in the panel, when spawned or when changed.
+#[allow(clippy::needless_pass_by_value)]
+fn update_language_value(
+    ui_language: Res<UiLanguage>,
+    localization: Res<Localization>,
+    row: Query<(Ref<LanguageRow>, &Children)>,
+    mut texts: Query<&mut Text, With<SettingValue>>,
+) {
+    for (row, children) in &row {
+        if !ui_language.is_changed() && !localization.is_changed() && !row.is_added() {
+            continue;
+        }
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.0 = ui_language.0.map_or_else(
+                    || localization.text("setting-auto"),
+                    |language| Localization::preset(language).text("language-name"),
+                );
+            }
+        }
+    }
+}
+
+/// Show the current theme in the panel, when spawned or when changed.
+#[allow(clippy::needless_pass_by_value)]
+fn update_theme_value(
+    theme: Res<Theme>,
+    row: Query<(Ref<ThemeRow>, &Children)>,
+    mut texts: Query<&mut Text, With<SettingValue>>,
+) {
+    for (row, children) in &row {
+        if !theme.is_changed() && !row.is_added() {
+            continue;
+        }
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.0.clone_from(&theme.name);
+            }
+        }
+    }
+}
+
+/// Show the key click settings in the panel, when spawned or when changed.
+#[allow(clippy::needless_pass_by_value)]
+fn update_click_values(
+    feedback: Res<ClickFeedback>,
+    localization: Res<Localization>,
+    rows: Query<(Ref<ClickRow>, &Children)>,
+    mut texts: Query<&mut Text, With<SettingValue>>,
+) {
+    for (row, children) in &rows {
+        if !feedback.is_changed() && !localization.is_changed() && !row.is_added() {
+            continue;
+        }
+
+        for child in children {
+            if let Ok(mut text) = texts.get_mut(*child) {
+                text.0 = row.0.value(&feedback, &localization);
+            }
+        }
+    }
+}
+
+/// Name of a notation in the current language, e.g. `fix2`.
+fn notation_name(notation: Notation, localization: &Localization) -> String {
+    let (id, decimals) = match notation {
+        Notation::Auto => return localization.text("notation-auto"),
+        Notation::Fix(decimals) => ("notation-fix", decimals),
+        Notation::Sci(decimals) => ("notation-sci", decimals),
+        Notation::Eng(decimals) => ("notation-eng", decimals),
+    };
+
+    localization.text_with(id, &[("decimals", decimals.to_string())])
+}
+
+/// Name of a rounding mode in the current language, e.g. `half-even`.
+fn rounding_name(rounding: RoundingMode, localization: &Localization) -> String {
+    localization.text(&format!("rounding-{rounding}"))
+}
+
+/// Summary of the settings in the current language, e.g. `AUTO 1DP HALF-UP`.
+pub fn localized_indicator(format: &NumberFormat, localization: &Localization) -> String {
+    let notation = notation_name(format.notation, localization).to_uppercase();
+    match format.precision {
+        Some(decimals) => localization.text_with(
+            "indicator-precision",
+            &[
+                ("notation", notation),
+                ("decimals", decimals.to_string()),
+                (
+                    "rounding",
+                    rounding_name(format.rounding, localization).to_uppercase(),
+                ),
+            ],
+        ),
+        None => notation,
+    }
+}
+
+/// Show the summary of the current settings on the display, when spawned or when changed.
+#[allow(clippy::needless_pass_by_value)]
+fn update_settings_indicator(
+    format: Res<NumberFormat>,
+    localization: Res<Localization>,
+    mut indicator: Query<&mut Text, With<SettingsIndicator>>,
+) {
+    for mut text in &mut indicator {
+        if format.is_changed() || localization.is_changed() || text.is_added() {
+            text.0 = localized_indicator(&format, &localization);
+        }
+    }
+}
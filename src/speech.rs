@@ -0,0 +1,249 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::input_focus::InputFocus;
+use bevy::prelude::*;
+use bevy::text::{EditableText, TextEditChange};
+
+use crate::action::{CalcAction, CalcApplied, apply_calc_actions};
+use crate::button::{DOT_BUTTON, SUB_BUTTON};
+use crate::config::CalcConfig;
+use crate::format::NumberFormat;
+use crate::localization::Localization;
+use crate::operation::OperationMetadata;
+
+/// Voice feedback, speaking each key press and every result.
+#[derive(Resource, Default, Clone, Debug, PartialEq, Eq)]
+pub struct VoiceFeedback {
+    pub enabled: bool,
+}
+
+/// A text to speak.
+#[derive(Message, Clone, Debug)]
+pub struct Utterance(pub String);
+
+/// What to say about an applied action: the name of the pressed button, then the computed value.
+pub fn utterance(
+    applied: &CalcApplied,
+    format: &NumberFormat,
+    localization: &Localization,
+) -> Option<String> {
+    let result = applied
+        .evaluation
+        .as_ref()
+        .map(|evaluation| format.format(evaluation.value));
+
+    match (&applied.action, result) {
+        (CalcAction::Press(button), Some(result)) => {
+            Some(format!("{}, {result}", localization.button_name(*button)))
+        }
+        (CalcAction::Press(button), None) => Some(localization.button_name(*button)),
+        (CalcAction::Evaluate(_), result) => result,
+    }
+}
+
+/// What to say about a text typed on the display: the name of each typed key.
+pub fn typed_utterance(typed: &str, localization: &Localization) -> String {
+    typed
+        .chars()
+        .map(|c| match c {
+            '.' | ',' => localization.button_name(DOT_BUTTON),
+            '-' => localization.button_name(SUB_BUTTON),
+            c => c.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Speak the key presses and the results when the voice feedback is enabled (toggled with `F3`),
+/// through a local TTS backend on desktop (with the `speech` feature), or the Web Speech API.
+pub struct SpeechPlugin;
+
+impl Plugin for SpeechPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<Utterance>();
+        app.init_resource::<VoiceFeedback>();
+        app.init_resource::<PreviousDisplay>();
+        app.init_non_send_resource::<SpeechBackend>();
+        app.add_systems(Startup, enable_configured_speech);
+        app.add_systems(
+            Update,
+            (
+                toggle_voice_feedback,
+                announce.after(apply_calc_actions),
+                speak,
+            )
+                .chain(),
+        );
+        app.add_systems(Update, remember_display.after(apply_calc_actions));
+        app.add_observer(announce_typing);
+    }
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn enable_configured_speech(config: Option<Res<CalcConfig>>, mut voice: ResMut<VoiceFeedback>) {
+    voice.enabled = config.is_some_and(|config| config.speech);
+}
+
+/// Toggle the voice feedback with `F3`, saying so when enabled.
+#[allow(clippy::needless_pass_by_value)]
+fn toggle_voice_feedback(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut voice: ResMut<VoiceFeedback>,
+    localization: Res<Localization>,
+    mut utterances: MessageWriter<Utterance>,
+) {
+    if keys.just_pressed(KeyCode::F3) {
+        voice.enabled = !voice.enabled;
+
+        info!(
+            "Voice feedback: {}",
+            if voice.enabled { "on" } else { "off" }
+        );
+
+        if voice.enabled {
+            utterances.write(Utterance(localization.text("speech-voice-on")));
+        }
+    }
+}
+
+/// Say the applied actions, when the voice feedback is enabled.
+#[allow(clippy::needless_pass_by_value)]
+fn announce(
+    mut applied: MessageReader<CalcApplied>,
+    voice: Res<VoiceFeedback>,
+    format: Res<NumberFormat>,
+    localization: Res<Localization>,
+    mut utterances: MessageWriter<Utterance>,
+) {
+    for applied in applied.read() {
+        if voice.enabled
+            && let Some(text) = utterance(applied, &format, &localization)
+        {
+            utterances.write(Utterance(text));
+        }
+    }
+}
+
+/// The display value before the last edit, to tell the typed text.
+#[derive(Resource, Default)]
+struct PreviousDisplay(String);
+
+/// Remember the display value once changed by the calculator (e.g. a button press or a result),
+/// the typed text being told from it.
+#[allow(clippy::needless_pass_by_value)]
+fn remember_display(
+    display: Single<&EditableText, With<OperationMetadata>>,
+    mut previous: ResMut<PreviousDisplay>,
+) {
+    let value = display.value();
+    if previous.0 != value {
+        previous.0 = value.to_string();
+    }
+}
+
+/// Say the keys typed on the display, when the voice feedback is enabled (the buttons and the
+/// results being said by `announce`).
+#[allow(clippy::needless_pass_by_value)]
+fn announce_typing(
+    _change: On<TextEditChange>,
+    display: Single<(Entity, &EditableText, &OperationMetadata)>,
+    input_focus: Res<InputFocus>,
+    voice: Res<VoiceFeedback>,
+    localization: Res<Localization>,
+    mut utterances: MessageWriter<Utterance>,
+    mut previous: ResMut<PreviousDisplay>,
+) {
+    let (entity, editable, op_metadata) = *display;
+    let text = editable.value().to_string();
+
+    // Only the text inserted at the end of the display, when typed in it
+    if voice.enabled
+        && input_focus.get() == Some(entity)
+        && !op_metadata.is_shown(&text)
+        && let Some(typed) = text.strip_prefix(previous.0.as_str())
+        && !typed.is_empty()
+    {
+        utterances.write(Utterance(typed_utterance(typed, &localization)));
+    }
+
+    previous.0 = text;
+}
+
+/// Speak the utterances, each one interrupting the previous one to keep up with fast typing.
+#[allow(clippy::needless_pass_by_value)]
+fn speak(
+    mut utterances: MessageReader<Utterance>,
+    mut backend: NonSendMut<SpeechBackend>,
+    format: Res<NumberFormat>,
+) {
+    for Utterance(text) in utterances.read() {
+        debug!("Speaking: {text}");
+
+        if let Err(err) = backend.speak(text, format.locale.tag()) {
+            warn!("Failed to speak '{text}': {err}");
+        }
+    }
+}
+
+/// The local TTS backend, created when first speaking.
+#[cfg(all(feature = "speech", not(target_arch = "wasm32")))]
+#[derive(Default)]
+struct SpeechBackend(Option<tts::Tts>);
+
+#[cfg(all(feature = "speech", not(target_arch = "wasm32")))]
+impl SpeechBackend {
+    fn speak(&mut self, text: &str, _language: &str) -> Result {
+        if self.0.is_none() {
+            self.0 = Some(tts::Tts::default()?);
+        }
+        if let Some(tts) = &mut self.0 {
+            tts.speak(text, true)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// No TTS backend, without the `speech` feature.
+#[cfg(all(not(feature = "speech"), not(target_arch = "wasm32")))]
+#[derive(Default)]
+struct SpeechBackend;
+
+#[cfg(all(not(feature = "speech"), not(target_arch = "wasm32")))]
+impl SpeechBackend {
+    #[allow(clippy::unused_self, clippy::unnecessary_wraps)]
+    fn speak(&mut self, _text: &str, _language: &str) -> Result {
+        warn_once!("No voice feedback, the calculator is built without the `speech` feature");
+
+        Ok(())
+    }
+}
+
+/// The Web Speech API of the browser.
+#[cfg(target_arch = "wasm32")]
+#[derive(Default)]
+struct SpeechBackend;
+
+#[cfg(target_arch = "wasm32")]
+impl SpeechBackend {
+    #[allow(clippy::unused_self)]
+    fn speak(&mut self, text: &str, language: &str) -> Result {
+        let synthesis = web_sys::window()
+            .ok_or("No browser window")?
+            .speech_synthesis()
+            .map_err(|_| "No speech synthesis in the browser")?;
+        let utterance = web_sys::SpeechSynthesisUtterance::new_with_text(text)
+            .map_err(|_| "Failed to create the utterance")?;
+        utterance.set_lang(language);
+
+        synthesis.cancel();
+        synthesis.speak(&utterance);
+
+        Ok(())
+    }
+}
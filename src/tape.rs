@@ -0,0 +1,656 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::input::mouse::MouseScrollUnit;
+use bevy::input_focus::{FocusCause, InputFocus};
+use bevy::prelude::*;
+use bevy::text::{EditableText, TextEditChange};
+use std::fmt;
+
+use crate::action::{CalcAction, CalcApplied, apply_calc_actions, reset_editable};
+use crate::button::{
+    ADD_BUTTON, ButtonVariant, CLEAR_BUTTON, DIVIDE_BUTTON, EQUAL_BUTTON, MULTIPLY_BUTTON,
+    POURCENT_BUTTON, SUB_BUTTON,
+};
+use crate::export::{self, FileButton, csv_field};
+use crate::expression_line::prettify;
+use crate::format::NumberFormat;
+use crate::localization::Localized;
+use crate::theme::{Theme, ThemeRole};
+
+/// Maximum number of lines kept on the tape, the oldest ones are dropped beyond.
+const MAX_LINES: usize = 1000;
+/// Maximum length of a note, in characters.
+pub const MAX_NOTE_LENGTH: usize = 40;
+/// Height of a line of the tape list, to scroll with the mouse wheel.
+const SCROLL_LINE_HEIGHT: f32 = 20.;
+
+/// The marker printed after an entry of the tape, as on an adding machine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapeMark {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Percent,
+    /// The last operand of an operation
+    Equals,
+    /// An expression evaluated at once (e.g. pasted)
+    Expression,
+    /// A computed value, the operations may go on with it
+    Subtotal,
+    /// The last computed value, when cleared
+    Total,
+    /// The ongoing operation, when cleared
+    Clear,
+}
+
+impl TapeMark {
+    fn of_operator(button: ButtonVariant) -> Option<Self> {
+        match button {
+            ADD_BUTTON => Some(Self::Add),
+            SUB_BUTTON => Some(Self::Sub),
+            MULTIPLY_BUTTON => Some(Self::Mul),
+            DIVIDE_BUTTON => Some(Self::Div),
+            _ => None,
+        }
+    }
+
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "×",
+            Self::Div => "÷",
+            Self::Percent => "%",
+            Self::Equals | Self::Expression => "=",
+            Self::Subtotal => "S",
+            Self::Total => "T",
+            Self::Clear => "C",
+        }
+    }
+}
+
+impl fmt::Display for TapeMark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// A line of the tape.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TapeLine {
+    /// The number (unformatted), or the evaluated expression
+    pub entry: String,
+    pub mark: TapeMark,
+    pub note: String,
+}
+
+impl TapeLine {
+    /// The entry as shown on the tape, numbers being formatted after the locale.
+    pub fn formatted_entry(&self, format: &NumberFormat) -> String {
+        self.entry
+            .parse::<f64>()
+            .map_or_else(|_| prettify(&self.entry), |value| format.format(value))
+    }
+}
+
+/// A file format the tape can be exported to.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapeFormat {
+    #[default]
+    Text,
+    Csv,
+    /// A standalone page, to print from the browser
+    Html,
+}
+
+impl TapeFormat {
+    pub const ALL: [Self; 3] = [Self::Text, Self::Csv, Self::Html];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Text => "TXT",
+            Self::Csv => "CSV",
+            Self::Html => "HTML",
+        }
+    }
+
+    pub const fn file_name(self) -> &'static str {
+        match self {
+            Self::Text => "tape.txt",
+            Self::Csv => "tape.csv",
+            Self::Html => "tape.html",
+        }
+    }
+
+    pub const fn mime_type(self) -> &'static str {
+        match self {
+            Self::Text => "text/plain",
+            Self::Csv => "text/csv",
+            Self::Html => "text/html",
+        }
+    }
+}
+
+/// An adding-machine style tape, logging every entry and operator line by line.
+#[derive(Resource, Default, Clone, Debug, PartialEq)]
+pub struct Tape {
+    lines: Vec<TapeLine>,
+    /// Whether the last line is an operator with no operand typed since, so another operator replaces it
+    awaiting_operand: bool,
+    /// The line annotated by the note editor
+    selected: Option<usize>,
+}
+
+impl Tape {
+    pub fn lines(&self) -> &[TapeLine] {
+        &self.lines
+    }
+
+    pub const fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Select the line annotated by the note editor.
+    pub fn select(&mut self, index: usize) {
+        if index < self.lines.len() {
+            self.selected = Some(index);
+        }
+    }
+
+    /// Annotate a line, the note being truncated to its maximum length.
+    pub fn set_note(&mut self, index: usize, note: &str) -> Result {
+        let line = self.lines.get_mut(index).ok_or("No such tape line")?;
+        line.note = note.chars().take(MAX_NOTE_LENGTH).collect();
+
+        Ok(())
+    }
+
+    fn push(&mut self, entry: String, mark: TapeMark) {
+        if self.lines.len() == MAX_LINES {
+            self.lines.remove(0);
+            self.selected = self.selected.and_then(|index| index.checked_sub(1));
+        }
+        self.lines.push(TapeLine {
+            entry,
+            mark,
+            note: String::new(),
+        });
+    }
+
+    /// Log an applied action, given the display value it was applied to.
+    pub fn record(&mut self, applied: &CalcApplied, format: &NumberFormat) {
+        let operand = format.parse(&applied.display).ok();
+        let result = applied
+            .evaluation
+            .as_ref()
+            .map(|evaluation| evaluation.value);
+
+        match &applied.action {
+            CalcAction::Press(button) => {
+                if let Some(mark) = TapeMark::of_operator(*button) {
+                    if self.awaiting_operand
+                        && let Some(line) = self.lines.last_mut()
+                    {
+                        line.mark = mark;
+                    } else if let Some(operand) = operand {
+                        self.push(operand.to_string(), mark);
+                    }
+                    self.awaiting_operand = true;
+                    return;
+                }
+
+                match (*button, operand, result) {
+                    (EQUAL_BUTTON, Some(operand), Some(result)) => {
+                        self.push(operand.to_string(), TapeMark::Equals);
+                        self.push(result.to_string(), TapeMark::Subtotal);
+                    }
+                    (POURCENT_BUTTON, Some(operand), Some(result)) => {
+                        self.push(operand.to_string(), TapeMark::Percent);
+                        self.push(result.to_string(), TapeMark::Subtotal);
+                    }
+                    (CLEAR_BUTTON, _, _) => {
+                        match self
+                            .lines
+                            .last()
+                            .map(|line| (line.mark, line.entry.clone()))
+                        {
+                            Some((TapeMark::Subtotal, entry)) => self.push(entry, TapeMark::Total),
+                            Some((TapeMark::Total | TapeMark::Clear, _)) | None => {}
+                            Some(_) => self.push(String::new(), TapeMark::Clear),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            CalcAction::Evaluate(expression) => {
+                if let Some(result) = result {
+                    self.push(expression.clone(), TapeMark::Expression);
+                    self.push(result.to_string(), TapeMark::Subtotal);
+                }
+            }
+        }
+        self.awaiting_operand = false;
+    }
+
+    /// Export the lines to the given format.
+    pub fn export(&self, tape_format: TapeFormat, format: &NumberFormat) -> String {
+        match tape_format {
+            TapeFormat::Text => self.to_text(format),
+            TapeFormat::Csv => self.to_csv(),
+            TapeFormat::Html => self.to_html(format),
+        }
+    }
+
+    /// One line per entry, the entries aligned on the right as printed by an adding machine.
+    fn to_text(&self, format: &NumberFormat) -> String {
+        let entries: Vec<String> = self
+            .lines
+            .iter()
+            .map(|line| line.formatted_entry(format))
+            .collect();
+        let width = entries
+            .iter()
+            .map(|entry| entry.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        self.lines
+            .iter()
+            .zip(entries)
+            .map(|(line, entry)| {
+                format!("{entry:>width$} {}  {}", line.mark, line.note)
+                    .trim_end()
+                    .to_string()
+            })
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// The entries unformatted, to be read by spreadsheets.
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("entry,mark,note\n");
+        for line in &self.lines {
+            csv += &format!(
+                "{},{},{}\n",
+                csv_field(&line.entry),
+                csv_field(line.mark.symbol()),
+                csv_field(&line.note)
+            );
+        }
+        csv
+    }
+
+    fn to_html(&self, format: &NumberFormat) -> String {
+        let rows: String = self
+            .lines
+            .iter()
+            .map(|line| {
+                format!(
+                    "<tr><td class=\"entry\">{}</td><td>{}</td><td>{}</td></tr>\n",
+                    html_escape(&line.formatted_entry(format)),
+                    line.mark,
+                    html_escape(&line.note)
+                )
+            })
+            .collect();
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Tape</title>\n\
+             <style>body {{ font-family: monospace; }} td {{ padding: 0 8px; }} \
+             td.entry {{ text-align: right; }}</style>\n</head>\n<body>\n<table>\n{rows}</table>\n\
+             </body>\n</html>\n"
+        )
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The tape panel, shown over the buttons.
+#[derive(Component, Default, Clone)]
+pub struct TapePanel;
+
+/// The scrollable list of the tape lines, in the tape panel.
+#[derive(Component, Default, Clone)]
+pub struct TapeList;
+
+/// A line of the tape list, selected for its note when clicked.
+#[derive(Component, Clone, Copy)]
+pub struct TapeLineRow(pub usize);
+
+/// The note of a line of the tape list.
+#[derive(Component, Default, Clone)]
+pub struct TapeLineNote;
+
+/// The editor of the note of the selected line, in the tape panel.
+#[derive(Component, Default, Clone)]
+pub struct TapeNoteEditor;
+
+/// A button of the tape panel, exporting the tape when clicked.
+#[derive(Component, Default, Clone, Copy)]
+pub struct TapeExport(pub TapeFormat);
+
+/// Log the entries and operators on a paper tape, shown in a panel toggled with `F4`, where the
+/// lines can be annotated and the tape exported.
+pub struct TapePlugin;
+
+impl Plugin for TapePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tape>();
+        app.add_systems(
+            Update,
+            (
+                toggle_tape_panel,
+                (record_tape.after(apply_calc_actions), update_tape_list).chain(),
+                update_note_editor,
+                tape_lines_state,
+            ),
+        );
+        app.add_observer(edit_note);
+    }
+}
+
+/// The tape panel, hidden until opened, to be placed over the buttons.
+pub fn panel() -> impl Scene {
+    let exports: Vec<Box<dyn Scene>> = TapeFormat::ALL
+        .into_iter()
+        .map(|tape_format| Box::new(export_button(tape_format)) as Box<dyn Scene>)
+        .collect();
+
+    bsn! {
+        Node {
+            display: Display::None,
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(8.),
+            padding: UiRect::all(Val::Px(12.)),
+        }
+        TapePanel
+        BackgroundColor::default()
+        ThemeRole::Panel
+        Children [
+            (
+                Node {
+                    column_gap: Val::Px(6.),
+                    align_items: AlignItems::Center,
+                }
+                Children [
+                    (
+                        Node { flex_grow: 1. }
+                        Localized("tape-title")
+                        Text::default()
+                        TextColor::default()
+                        ThemeRole::PanelText
+                    ),
+                    { exports },
+                ]
+            ),
+            (
+                Node {
+                    flex_grow: 1.,
+                    min_height: Val::Px(0.),
+                    flex_direction: FlexDirection::Column,
+                    overflow: Overflow::scroll_y(),
+                }
+                TapeList
+                ScrollPosition::default()
+                on(scroll_tape)
+            ),
+            (
+                Node {
+                    column_gap: Val::Px(8.),
+                    padding: UiRect::axes(Val::Px(8.), Val::Px(4.)),
+                    border_radius: BorderRadius::MAX,
+                }
+                BackgroundColor::default()
+                ThemeRole::Display
+                Children [
+                    (
+                        Localized("tape-note")
+                        Text::default()
+                        TextFont::from_font_size(14.)
+                        TextColor::default()
+                        ThemeRole::SecondaryText
+                    ),
+                    (
+                        Node { flex_grow: 1. }
+                        TapeNoteEditor
+                        EditableText::new("")
+                        TextFont::from_font_size(14.)
+                        TextColor::default()
+                        ThemeRole::DisplayText
+                    ),
+                ]
+            ),
+        ]
+    }
+}
+
+fn export_button(tape_format: TapeFormat) -> impl Scene {
+    bsn! {
+        Button
+        TapeExport({tape_format})
+        FileButton
+        Node {
+            padding: UiRect::axes(Val::Px(8.), Val::Px(2.)),
+            border: UiRect::all(Val::Px(2.)),
+            border_radius: BorderRadius::MAX,
+        }
+        BorderColor::default()
+        BackgroundColor::default()
+        on(on_export_click)
+        Children [(
+            Text({tape_format.label()})
+            TextFont::from_font_size(12.)
+            TextColor::default()
+            ThemeRole::PanelText
+        )]
+    }
+}
+
+/// Toggle the tape panel with `F4`, and close it with `Esc`.
+#[allow(clippy::needless_pass_by_value)]
+fn toggle_tape_panel(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut panel: Single<&mut Node, With<TapePanel>>,
+) {
+    if keys.just_pressed(KeyCode::F4) {
+        panel.display = if panel.display == Display::None {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    } else if keys.just_pressed(KeyCode::Escape) {
+        panel.display = Display::None;
+    }
+}
+
+/// Log the applied actions on the tape.
+#[allow(clippy::needless_pass_by_value)]
+fn record_tape(
+    mut applied: MessageReader<CalcApplied>,
+    mut tape: ResMut<Tape>,
+    format: Res<NumberFormat>,
+) {
+    for applied in applied.read() {
+        tape.record(applied, &format);
+    }
+}
+
+/// List the tape lines in the panel, when spawned or when changed, keeping the last line visible.
+#[allow(clippy::needless_pass_by_value)]
+fn update_tape_list(
+    mut commands: Commands,
+    tape: Res<Tape>,
+    format: Res<NumberFormat>,
+    list: Single<(Entity, Ref<TapeList>, &mut ScrollPosition)>,
+) {
+    let (list, marker, mut scroll_position) = list.into_inner();
+    if !tape.is_changed() && !format.is_changed() && !marker.is_added() {
+        return;
+    }
+
+    commands.entity(list).despawn_related::<Children>();
+    for (index, line) in tape.lines().iter().enumerate() {
+        commands
+            .spawn((
+                Button,
+                TapeLineRow(index),
+                Node {
+                    flex_shrink: 0.,
+                    justify_content: JustifyContent::SpaceBetween,
+                    column_gap: Val::Px(8.),
+                    padding: UiRect::horizontal(Val::Px(4.)),
+                },
+                BackgroundColor::default(),
+                ChildOf(list),
+            ))
+            .observe(on_tape_line_click)
+            .with_children(|row| {
+                row.spawn((
+                    TapeLineNote,
+                    Text(line.note.clone()),
+                    TextFont::from_font_size(12.),
+                    TextColor::default(),
+                    ThemeRole::SecondaryText,
+                ));
+                row.spawn((
+                    Text(format!("{} {}", line.formatted_entry(&format), line.mark)),
+                    TextFont::from_font_size(14.),
+                    TextColor::default(),
+                    ThemeRole::PanelText,
+                ));
+            });
+    }
+
+    // Clamped to the content height by the UI layout
+    scroll_position.y = f32::MAX;
+}
+
+/// Scroll the tape list with the mouse wheel.
+fn scroll_tape(scroll: On<Pointer<Scroll>>, mut list: Single<&mut ScrollPosition, With<TapeList>>) {
+    let line_height = match scroll.unit {
+        MouseScrollUnit::Line => SCROLL_LINE_HEIGHT,
+        MouseScrollUnit::Pixel => 1.,
+    };
+
+    list.y = (list.y - scroll.y * line_height).max(0.);
+}
+
+/// Select a line for the note editor when clicking it, and focus the editor.
+#[allow(clippy::needless_pass_by_value)]
+fn on_tape_line_click(
+    click: On<Pointer<Click>>,
+    rows: Query<&TapeLineRow>,
+    editor: Single<Entity, With<TapeNoteEditor>>,
+    mut tape: ResMut<Tape>,
+    mut input_focus: ResMut<InputFocus>,
+) -> Result {
+    let TapeLineRow(index) = *rows.get(click.entity)?;
+
+    debug!("Selecting tape line: {index}");
+
+    // Highlighted by `tape_lines_state`, without listing the lines again
+    tape.bypass_change_detection().select(index);
+    input_focus.set(*editor, FocusCause::Navigated);
+
+    Ok(())
+}
+
+/// Load the note of the selected line in the editor, when the selection changes.
+#[allow(clippy::needless_pass_by_value)]
+fn update_note_editor(
+    tape: Res<Tape>,
+    mut editor: Single<&mut EditableText, With<TapeNoteEditor>>,
+    mut selected: Local<Option<usize>>,
+) {
+    if tape.selected() == *selected {
+        return;
+    }
+    *selected = tape.selected();
+
+    let note = selected
+        .and_then(|index| tape.lines().get(index))
+        .map_or("", |line| &line.note);
+    reset_editable(&mut editor, note);
+}
+
+/// Save the edited note on the selected line, and show it on its row only, rather than listing
+/// the lines again (which would scroll to the last one).
+#[allow(clippy::needless_pass_by_value)]
+fn edit_note(
+    _change: On<TextEditChange>,
+    editor: Single<&EditableText, With<TapeNoteEditor>>,
+    mut tape: ResMut<Tape>,
+    rows: Query<(&TapeLineRow, &Children)>,
+    mut notes: Query<&mut Text, With<TapeLineNote>>,
+) -> Result {
+    let Some(index) = tape.selected() else {
+        return Ok(());
+    };
+
+    let note = editor.value().to_string();
+    if tape.lines().get(index).is_none_or(|line| line.note == note) {
+        return Ok(());
+    }
+
+    let tape = tape.bypass_change_detection();
+    tape.set_note(index, &note)?;
+
+    if let Some((_, children)) = rows.iter().find(|(TapeLineRow(row), _)| *row == index) {
+        let mut texts = notes.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            text.0.clone_from(&tape.lines()[index].note);
+        }
+    }
+
+    Ok(())
+}
+
+/// Export the tape when clicking an export button.
+#[allow(clippy::needless_pass_by_value)]
+fn on_export_click(
+    click: On<Pointer<Click>>,
+    buttons: Query<&TapeExport>,
+    tape: Res<Tape>,
+    format: Res<NumberFormat>,
+) -> Result {
+    let TapeExport(tape_format) = *buttons.get(click.entity)?;
+
+    debug!("Exporting the tape to: {}", tape_format.label());
+
+    export::save(
+        tape_format.file_name(),
+        tape_format.mime_type(),
+        &tape.export(tape_format, &format),
+    )
+}
+
+/// Highlight the hovered lines of the tape panel, and the selected one.
+#[allow(clippy::needless_pass_by_value)]
+fn tape_lines_state(
+    mut rows: Query<(&TapeLineRow, &Interaction, &mut BackgroundColor)>,
+    tape: Res<Tape>,
+    theme: Res<Theme>,
+) {
+    for (TapeLineRow(index), interaction, mut bg_color) in &mut rows {
+        let color = match interaction {
+            _ if tape.selected() == Some(*index) => theme.focus,
+            Interaction::Hovered | Interaction::Pressed => theme.function.hovered,
+            Interaction::None => Color::NONE,
+        };
+        bg_color.set_if_neq(BackgroundColor(color));
+    }
+}
@@ -0,0 +1,60 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::a11y::AccessibilityNode;
+use bevy::a11y::accesskit::{Live, Role, Toggled};
+
+use super::TestApp;
+use crate::viewport::DisplayViewport;
+
+#[test]
+fn buttons_have_a_spoken_name() {
+    let mut app = TestApp::new();
+
+    for (label, name) in [("*", "multiply"), ("+/-", "toggle sign"), ("7", "7")] {
+        let button = app.button(label);
+        let accessible = app.get::<AccessibilityNode>(button);
+        assert_eq!(accessible.role(), Role::Button);
+        assert_eq!(accessible.label(), Some(name));
+    }
+}
+
+#[test]
+fn current_operator_is_pressed() {
+    let mut app = TestApp::new();
+    let multiply = app.button("*");
+    let add = app.button("+");
+    let seven = app.button("7");
+
+    app.click_all(&["1", "2", "*"]);
+    assert_eq!(
+        app.get::<AccessibilityNode>(multiply).toggled(),
+        Some(Toggled::True)
+    );
+    assert_eq!(
+        app.get::<AccessibilityNode>(add).toggled(),
+        Some(Toggled::False)
+    );
+    assert_eq!(app.get::<AccessibilityNode>(seven).toggled(), None);
+
+    app.click_all(&["7", "="]);
+    assert_eq!(
+        app.get::<AccessibilityNode>(multiply).toggled(),
+        Some(Toggled::False)
+    );
+}
+
+#[test]
+fn display_is_a_live_region() {
+    let mut app = TestApp::new();
+    let viewport = app.find::<DisplayViewport>(|_| true);
+
+    app.click_all(&["1", "2", "*", "7", "="]);
+    let accessible = app.get::<AccessibilityNode>(viewport);
+    assert_eq!(accessible.live(), Some(Live::Polite));
+    assert_eq!(accessible.label(), Some("84"));
+}
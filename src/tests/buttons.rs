@@ -0,0 +1,241 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::action::CalcAction;
+use crate::engine::Calculator;
+use crate::expression_line::ExpressionLine;
+use crate::format::{Notation, NumberFormat};
+use crate::operation::CalcOperator;
+use crate::theme::Theme;
+
+#[test]
+fn starts_with_zero() {
+    let mut app = TestApp::new();
+
+    assert_eq!(app.display(), "0");
+    assert!(!app.operation().is_under_operation());
+}
+
+#[test]
+fn digit_replaces_initial_zero() {
+    let mut app = TestApp::new();
+
+    app.click("7");
+    assert_eq!(app.display(), "7");
+
+    app.click_all(&["0", "3"]);
+    assert_eq!(app.display(), "703");
+}
+
+#[test]
+fn dot_is_inserted_once() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["1", ".", "5", "."]);
+    assert_eq!(app.display(), "1.5");
+}
+
+#[test]
+fn invert_toggles_sign() {
+    let mut app = TestApp::new();
+
+    app.click("+/-");
+    assert_eq!(app.display(), "0");
+
+    app.click_all(&["4", "2", "+/-"]);
+    assert_eq!(app.display(), "-42");
+
+    app.click("+/-");
+    assert_eq!(app.display(), "42");
+}
+
+#[test]
+fn percent_divides_by_hundred() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["5", "0", "%"]);
+    assert_eq!(app.display(), "0.5");
+    assert!(!app.operation().is_under_operation());
+}
+
+#[test]
+fn operator_clears_display_and_keeps_left_operand() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["1", "2", "*"]);
+
+    let operation = app.operation();
+    assert_eq!(app.display(), "0");
+    assert_eq!(operation.left_operand(), Some(12.));
+    assert_eq!(operation.operator(), Some(CalcOperator::Mul));
+}
+
+#[test]
+fn operations() {
+    for (buttons, expected) in [
+        (["8", "+", "5", "="], "13"),
+        (["8", "-", "5", "="], "3"),
+        (["8", "*", "5", "="], "40"),
+        (["8", "/", "5", "="], "1.6"),
+    ] {
+        let mut app = TestApp::new();
+
+        app.click_all(&buttons);
+        assert_eq!(app.display(), expected, "{buttons:?}");
+        assert!(!app.operation().is_under_operation());
+    }
+}
+
+#[test]
+fn result_is_the_next_left_operand() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["6", "*", "7", "=", "-", "2", "="]);
+    assert_eq!(app.display(), "40");
+}
+
+#[test]
+fn queued_actions_are_applied_on_the_same_frame() {
+    let mut app = TestApp::new();
+
+    app.write_messages(["1", "2", "*", "7", "=", "+", "1", "="].map(CalcAction::Press));
+    assert_eq!(app.display(), "85");
+}
+
+#[test]
+fn results_are_chained_at_full_precision() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["1", "/", "3", "="]);
+    assert_eq!(app.display(), "0.333333333333");
+
+    app.click_all(&["*", "3", "="]);
+    assert_eq!(app.display(), "1");
+
+    app.click_all(&["C", "1", "/", "3", "=", "+/-", "*", "3", "="]);
+    assert_eq!(app.display(), "-1");
+
+    // Until edited
+    app.click_all(&["C", "1", "/", "3", "="]);
+    app.type_text("1");
+    app.click_all(&["*", "3", "="]);
+    assert_eq!(app.display(), "0.999999999999");
+}
+
+#[test]
+fn digits_start_a_new_number_after_a_result() {
+    let mut app = TestApp::new();
+
+    // Grouped digits
+    app.click_all(&["1", "2", "3", "4", "+", "0", "=", "5"]);
+    assert_eq!(app.display(), "5");
+    assert_eq!(app.operation().left_operand(), Some(5.));
+
+    app.click_all(&["C", "1", "2", "3", "4", "+", "0", "=", "."]);
+    assert_eq!(app.display(), "0.");
+
+    // Scientific notation
+    app.set_format(NumberFormat {
+        notation: Notation::Sci(2),
+        ..app.format()
+    });
+    app.click_all(&["C", "1", "2", "3", "4", "5", "+", "0", "="]);
+    assert_eq!(app.display(), "1.23e4");
+    app.click_all(&["5", "+", "1", "="]);
+    assert_eq!(app.display(), "6.00e0");
+}
+
+#[test]
+fn non_finite_results_are_shown() {
+    let mut app = TestApp::new();
+    let mut calculator = Calculator::default();
+    let sequences: [&[&str]; 4] = [
+        &["1", "/", "0", "="],
+        &["+/-", "*", "2", "="],
+        &["C", "0", "/", "0", "=", "%"],
+        &["5"],
+    ];
+
+    for (buttons, expected) in sequences.into_iter().zip(["inf", "-inf", "NaN", "5"]) {
+        app.click_all(buttons);
+        for button in buttons {
+            let _ = calculator.press(button);
+        }
+        assert_eq!(app.display(), expected);
+        assert_eq!(calculator.display(), expected);
+    }
+    assert_eq!(app.operation().left_operand(), Some(5.));
+}
+
+#[test]
+fn operator_without_number_uses_display() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["-", "3", "="]);
+    assert_eq!(app.display(), "-3");
+}
+
+#[test]
+fn equal_without_operation_does_nothing() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["9", "="]);
+    assert_eq!(app.display(), "9");
+}
+
+#[test]
+fn clear_resets_display_and_operation() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["9", "+", "1", "C"]);
+    assert_eq!(app.display(), "0");
+    assert!(app.operation().left_operand().is_none());
+    assert!(!app.operation().is_under_operation());
+}
+
+#[test]
+fn current_operator_is_highlighted() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["2", "+"]);
+
+    let theme = Theme::default();
+    let add = app.button("+");
+    let sub = app.button("-");
+    assert_eq!(
+        app.get::<BorderColor>(add),
+        BorderColor::all(theme.highlight_border)
+    );
+    assert_eq!(app.get::<BorderColor>(sub), BorderColor::all(theme.border));
+    assert_eq!(app.get::<BackgroundColor>(sub).0, theme.operator.normal);
+
+    app.click_all(&["3", "="]);
+    assert_eq!(app.get::<BorderColor>(add), BorderColor::all(theme.border));
+}
+
+#[test]
+fn expression_line_follows_operation() {
+    let mut app = TestApp::new();
+    let line = app.find::<ExpressionLine>(|_| true);
+
+    app.click_all(&["1", "2", "*"]);
+    assert_eq!(app.get::<Text>(line).0, "12 ×");
+
+    app.click_all(&["7", "="]);
+    assert_eq!(app.display(), "84");
+    assert_eq!(app.get::<Text>(line).0, "12 × 7 =");
+
+    // The computed expression is kept while typing the next number
+    app.click("3");
+    assert_eq!(app.get::<Text>(line).0, "12 × 7 =");
+
+    app.click("C");
+    assert_eq!(app.get::<Text>(line).0, "");
+}
@@ -0,0 +1,76 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::input::keyboard::Key;
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::click::{ClickFeedback, ClickTone};
+use crate::settings::{ClickRow, ClickSetting};
+
+#[test]
+fn clicks_are_muted_by_default() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["1", "+"]);
+    assert_eq!(app.count::<ClickTone>(), 0);
+}
+
+#[test]
+fn clicks_have_distinct_tones() {
+    let mut app = TestApp::new();
+    app.press_key(KeyCode::F5, Key::F5);
+
+    app.click("1");
+    assert_eq!(app.count::<ClickTone>(), 1);
+    app.find::<ClickTone>(|tone| *tone == ClickTone::Digit);
+
+    app.click("/");
+    app.find::<ClickTone>(|tone| *tone == ClickTone::Operator);
+
+    // A division by zero
+    app.click_all(&["0", "="]);
+    assert_eq!(app.count::<ClickTone>(), 4);
+    app.find::<ClickTone>(|tone| *tone == ClickTone::Error);
+
+    assert_eq!(ClickTone::of_button("."), ClickTone::Digit);
+    assert_eq!(ClickTone::of_button("+/-"), ClickTone::Operator);
+}
+
+#[test]
+fn volume_is_switched() {
+    let mut app = TestApp::new();
+    assert_eq!(app.resource::<ClickFeedback>().volume, 0.5);
+
+    for expected in [0.75, 1., 0.25, 0.5] {
+        app.press_key(KeyCode::F6, Key::F6);
+        assert_eq!(app.resource::<ClickFeedback>().volume, expected);
+    }
+}
+
+#[test]
+fn clicks_are_switched_from_the_settings() {
+    let mut app = TestApp::new();
+    let sound = app.find::<ClickRow>(|row| row.0 == ClickSetting::Sound);
+    let volume = app.find::<ClickRow>(|row| row.0 == ClickSetting::Volume);
+    let vibration = app.find::<ClickRow>(|row| row.0 == ClickSetting::Vibration);
+
+    app.click_entity(sound);
+    app.click_entity(volume);
+    app.click_entity(vibration);
+    assert_eq!(
+        app.resource::<ClickFeedback>(),
+        ClickFeedback {
+            volume: 0.75,
+            muted: false,
+            vibration: false,
+        }
+    );
+
+    app.click("1");
+    assert_eq!(app.count::<ClickTone>(), 1);
+}
@@ -0,0 +1,65 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use super::TestApp;
+use crate::clipboard::{Paste, Pasted, sanitize};
+use crate::format::NumberFormat;
+use crate::locale::Locale;
+
+#[test]
+fn pasted_text_is_sanitized() {
+    let us = NumberFormat::default();
+    let french = NumberFormat {
+        locale: Locale::FrFr,
+        ..NumberFormat::default()
+    };
+
+    for (text, format, expected) in [
+        ("1,234.5", &us, Pasted::Number(1234.5)),
+        (" $ 1,000 \n", &us, Pasted::Number(1000.)),
+        ("-42", &us, Pasted::Number(-42.)),
+        ("1\u{202F}234,5 €", &french, Pasted::Number(1234.5)),
+        (
+            "12 × (3 + 4)",
+            &us,
+            Pasted::Expression("12*(3+4)".to_string()),
+        ),
+        (
+            "1,5 + 2,5",
+            &french,
+            Pasted::Expression("1.5+2.5".to_string()),
+        ),
+    ] {
+        assert_eq!(sanitize(text, format).ok(), Some(expected), "{text}");
+    }
+
+    assert!(sanitize(" € ", &us).is_err());
+}
+
+#[test]
+fn pasted_number_replaces_the_display() {
+    let mut app = TestApp::new();
+    app.click_all(&["7", "+"]);
+
+    app.write_message(Paste("1,234".to_string()));
+    assert_eq!(app.display(), "1,234");
+
+    app.click("=");
+    assert_eq!(app.display(), "1,241");
+}
+
+#[test]
+fn pasted_expression_is_evaluated() {
+    let mut app = TestApp::new();
+
+    app.write_message(Paste("12 × (3 + 4)".to_string()));
+    assert_eq!(app.display(), "84");
+
+    // Invalid expressions are ignored
+    app.write_message(Paste("12 +".to_string()));
+    assert_eq!(app.display(), "84");
+}
@@ -0,0 +1,72 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use crate::format::{Notation, NumberFormat};
+
+fn format(notation: Notation, value: f64) -> String {
+    NumberFormat {
+        notation,
+        ..NumberFormat::default()
+    }
+    .format(value)
+}
+
+#[test]
+fn auto_notation() {
+    assert_eq!(format(Notation::Auto, 0.), "0");
+    assert_eq!(format(Notation::Auto, 0.1 + 0.2), "0.3");
+    assert_eq!(format(Notation::Auto, -1_234_567.5), "-1,234,567.5");
+    assert_eq!(format(Notation::Auto, 1e20), "1e20");
+    assert_eq!(format(Notation::Auto, 1.5e-7), "1.5e-7");
+    assert_eq!(format(Notation::Auto, 0.000_012_5), "0.0000125");
+}
+
+#[test]
+fn fixed_notations() {
+    assert_eq!(format(Notation::Fix(2), 1234.5), "1,234.50");
+    assert_eq!(format(Notation::Sci(3), 123_456.), "1.235e5");
+    assert_eq!(format(Notation::Eng(2), 123_456.), "123.46e3");
+    assert_eq!(format(Notation::Eng(1), 999_999.), "1.0e6");
+    assert_eq!(format(Notation::Eng(2), 0.0042), "4.20e-3");
+}
+
+#[test]
+fn fits_display_width() {
+    let number_format = NumberFormat::default();
+
+    let text = number_format.format(123_456_789_012_345.);
+    assert!(text.chars().count() <= number_format.max_width, "{text}");
+    assert_eq!(text, "1.23456789012e14");
+}
+
+#[test]
+fn parses_grouped_values() {
+    let number_format = NumberFormat::default();
+
+    assert_eq!(number_format.parse("-1,234,567.5").ok(), Some(-1_234_567.5));
+    assert_eq!(number_format.parse("1.5e-7").ok(), Some(1.5e-7));
+    assert!(number_format.parse("1.2.3").is_err());
+}
+
+#[test]
+fn notation_round_trips() {
+    for notation in [
+        Notation::Auto,
+        Notation::Fix(2),
+        Notation::Sci(4),
+        Notation::Eng(3),
+    ] {
+        assert_eq!(
+            notation.to_string().parse::<Notation>().ok(),
+            Some(notation)
+        );
+    }
+
+    for notation in ["fi", "fixé", "éèà2", "sci"] {
+        assert!(notation.parse::<Notation>().is_err(), "{notation}");
+    }
+}
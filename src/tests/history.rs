@@ -0,0 +1,162 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::export::OpenedFile;
+use crate::history::{History, HistoryEntry, HistoryFormat, HistoryList};
+use crate::responsive::{DESIGN_SIZE, WindowFit};
+
+#[test]
+fn computed_values_are_listed() {
+    let mut app = TestApp::new();
+    let list = app.find::<HistoryList>(|_| true);
+    assert_eq!(app.get::<Text>(list).0, "");
+
+    app.click_all(&["1", "2", "*", "7", "="]);
+    app.click_all(&["5", "0", "%"]);
+    assert_eq!(app.get::<Text>(list).0, "12 × 7 = 84\n50 % = 0.5");
+}
+
+#[test]
+fn window_fit() {
+    assert_eq!(
+        WindowFit::new(DESIGN_SIZE, 6),
+        WindowFit {
+            scale: 1.,
+            wide: false
+        }
+    );
+
+    // Scaled after the smallest side
+    let tall = WindowFit::new(Vec2::new(660., 945.), 6);
+    assert_eq!(tall.scale, 2.);
+    assert!(!tall.wide);
+
+    // The history panel takes 40% of the width
+    let wide = WindowFit::new(Vec2::new(1100., 315.), 6);
+    assert_eq!(wide.scale, 1.);
+    assert!(wide.wide);
+
+    assert_eq!(WindowFit::new(Vec2::new(50., 50.), 6).scale, 0.5);
+
+    // A single row of buttons, as in mini mode
+    let mini = WindowFit::new(Vec2::new(330., 105.), 2);
+    assert_eq!(mini.scale, 1.);
+    assert!(!mini.wide);
+}
+
+#[test]
+fn history_is_exported_and_imported() {
+    let mut app = TestApp::new();
+    app.click_all(&["1", "2", "*", "7", "="]);
+
+    let history = app.resource::<History>();
+    let [entry] = history.entries() else {
+        panic!("Expected a single entry");
+    };
+    assert_eq!(entry.expression, "12 * 7");
+    assert_eq!(entry.value, 84.);
+    assert!(entry.timestamp > 0);
+    assert_eq!(entry.mode, "basic");
+
+    let mut history = History::default();
+    history.push(HistoryEntry {
+        expression: "1, 2".to_string(),
+        value: 0.5,
+        timestamp: 1_760_000_000,
+        mode: "mini".to_string(),
+    });
+    let csv = history.export(HistoryFormat::Csv).unwrap();
+    assert_eq!(
+        csv,
+        "expression,result,timestamp,mode\n\"1, 2\",0.5,1760000000,mini\n"
+    );
+    assert!(
+        history
+            .export(HistoryFormat::Json)
+            .unwrap()
+            .contains("\"result\": 0.5")
+    );
+
+    for history_format in [HistoryFormat::Csv, HistoryFormat::Json] {
+        let exported = history.export(history_format).unwrap();
+        assert_eq!(History::import(history_format, &exported).unwrap(), history);
+    }
+    assert!(History::import(HistoryFormat::Csv, "12 * 7,84").is_err());
+
+    // The quoted fields span several lines
+    let mut history = History::default();
+    history.push(HistoryEntry {
+        expression: "1 +\n2".to_string(),
+        value: 3.,
+        timestamp: 1_760_000_000,
+        mode: "basic".to_string(),
+    });
+    let csv = history.export(HistoryFormat::Csv).unwrap();
+    assert_eq!(History::import(HistoryFormat::Csv, &csv).unwrap(), history);
+    let crlf = "expression,result,timestamp,mode\r\n6 / 3,2,1760000000,basic\r\n";
+    assert_eq!(
+        History::import(HistoryFormat::Csv, crlf).unwrap().entries()[0].value,
+        2.
+    );
+    assert!(History::import(HistoryFormat::Json, "{}").is_err());
+}
+
+#[test]
+fn non_finite_values_are_exported() {
+    let mut history = History::default();
+    for (expression, value) in [("1 / 0", f64::INFINITY), ("0 / 0", f64::NAN)] {
+        history.push(HistoryEntry {
+            expression: expression.to_string(),
+            value,
+            timestamp: 1_760_000_000,
+            mode: "basic".to_string(),
+        });
+    }
+
+    for history_format in [HistoryFormat::Csv, HistoryFormat::Json] {
+        let exported = history.export(history_format).unwrap();
+        let imported = History::import(history_format, &exported).unwrap();
+        let [infinity, nan] = imported.entries() else {
+            panic!("Expected two entries");
+        };
+        assert_eq!(infinity.value, f64::INFINITY);
+        assert!(nan.value.is_nan());
+    }
+    assert!(
+        history
+            .export(HistoryFormat::Json)
+            .unwrap()
+            .contains("\"result\": \"inf\"")
+    );
+}
+
+#[test]
+fn imported_files_replace_the_history() {
+    let mut app = TestApp::new();
+    let list = app.find::<HistoryList>(|_| true);
+    app.click_all(&["1", "+", "1", "="]);
+
+    app.write_message(OpenedFile {
+        name: "session.CSV".to_string(),
+        contents: "expression,result,timestamp,mode\n6 / 3,2,1760000000,basic\n".to_string(),
+    });
+    assert_eq!(app.get::<Text>(list).0, "6 ÷ 3 = 2");
+
+    // Unknown or invalid files are ignored
+    app.write_message(OpenedFile {
+        name: "notes.txt".to_string(),
+        contents: "expression,result,timestamp,mode\n".to_string(),
+    });
+    app.write_message(OpenedFile {
+        name: "session.json".to_string(),
+        contents: "[{}]".to_string(),
+    });
+    assert_eq!(app.get::<Text>(list).0, "6 ÷ 3 = 2");
+}
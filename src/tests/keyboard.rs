@@ -0,0 +1,67 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::input::keyboard::Key;
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::theme::Theme;
+
+#[test]
+fn enter_without_focus_triggers_equal() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["4", "*", "5"]);
+    app.clear_focus();
+
+    app.press_key(KeyCode::Enter, Key::Enter);
+    assert_eq!(app.display(), "20");
+}
+
+#[test]
+fn enter_activates_clicked_button() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["4", "2"]);
+    app.press_key(KeyCode::Enter, Key::Enter);
+    assert_eq!(app.display(), "422");
+}
+
+#[test]
+fn arrow_focuses_initial_button() {
+    let mut app = TestApp::new();
+
+    app.press_key(KeyCode::ArrowDown, Key::ArrowDown);
+
+    let clear = app.button("C");
+    assert_eq!(app.get::<BackgroundColor>(clear).0, Theme::default().focus);
+}
+
+#[test]
+fn enter_activates_focused_button() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["8", "+"]);
+    // Without a focused button, the first arrow key focuses the initial button (C)
+    app.clear_focus();
+    app.press_key(KeyCode::ArrowDown, Key::ArrowDown);
+
+    app.press_key(KeyCode::Enter, Key::Enter);
+    assert_eq!(app.display(), "0");
+    assert!(!app.operation().is_under_operation());
+}
+
+#[test]
+fn escape_hides_focus() {
+    let mut app = TestApp::new();
+
+    app.press_key(KeyCode::ArrowDown, Key::ArrowDown);
+    app.press_key(KeyCode::Escape, Key::Escape);
+
+    let clear = app.button("C");
+    assert_ne!(app.get::<BackgroundColor>(clear).0, Theme::default().focus);
+}
@@ -0,0 +1,94 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::button::CalcButton;
+use crate::layout::ButtonLayout;
+
+#[test]
+fn basic_layout_is_valid() {
+    let layout = ButtonLayout::preset("basic").expect("Valid built-in layout");
+    assert_eq!(layout, ButtonLayout::default());
+    assert_eq!((layout.rows, layout.columns), (5, 4));
+    assert_eq!(layout.buttons.len(), 19);
+
+    assert!(ButtonLayout::preset("hexadecimal").is_err());
+}
+
+#[test]
+fn invalid_layouts_are_rejected() {
+    for buttons in [
+        // Unknown action
+        r#"[(action: "sin", row: 0, column: 0)]"#,
+        // Outside the grid
+        r#"[(action: "7", row: 2, column: 0)]"#,
+        r#"[(action: "7", row: 0, column: 1, column_span: 2)]"#,
+        // Overlapping buttons
+        r#"[(action: "7", row: 0, column: 0, row_span: 2), (action: "8", row: 1, column: 0)]"#,
+        // Empty span
+        r#"[(action: "7", row: 0, column: 0, row_span: 0)]"#,
+        // Overflowing span
+        r#"[(action: "7", row: 1, column: 0, row_span: 65535)]"#,
+        r#"[(action: "7", row: 0, column: 65535, column_span: 1)]"#,
+    ] {
+        let ron = format!("(name: \"custom\", rows: 2, columns: 2, buttons: {buttons})");
+        assert!(ButtonLayout::parse(&ron).is_err(), "{buttons}");
+    }
+
+    // Too large grids, rejected before checking the buttons
+    for (rows, columns) in [(65535, 65535), (17, 4), (5, 17)] {
+        let ron = format!("(name: \"custom\", rows: {rows}, columns: {columns}, buttons: [])");
+        assert!(ButtonLayout::parse(&ron).is_err(), "{rows}x{columns}");
+    }
+}
+
+#[test]
+fn buttons_follow_the_layout() {
+    let mut app = TestApp::new();
+    assert_eq!(app.count::<CalcButton>(), 19);
+
+    let zero = app.button("0");
+    assert_eq!(
+        app.get::<Node>(zero).grid_column,
+        GridPlacement::start_span(1, 2)
+    );
+    let equal = app.button("=");
+    assert_eq!(
+        app.get::<Node>(equal).grid_row,
+        GridPlacement::start_span(6, 1)
+    );
+}
+
+#[test]
+fn layout_changes_live() {
+    let mut app = TestApp::new();
+    app.click("7");
+
+    let layout = ButtonLayout::parse(
+        r#"(
+            name: "custom",
+            rows: 2,
+            columns: 2,
+            buttons: [
+                (action: "1", row: 0, column: 0),
+                (action: "+", row: 0, column: 1),
+                (action: "=", row: 1, column: 0, column_span: 2),
+            ],
+        )"#,
+    )
+    .expect("Valid layout");
+    app.set_layout(layout);
+
+    assert_eq!(app.count::<CalcButton>(), 3);
+    // The display is kept
+    assert_eq!(app.display(), "7");
+
+    app.click_all(&["+", "1", "="]);
+    assert_eq!(app.display(), "8");
+}
@@ -0,0 +1,125 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use super::TestApp;
+use crate::button::{ADD_BUTTON, DOT_BUTTON, EQUAL_BUTTON, FIVE_BUTTON, ONE_BUTTON, TWO_BUTTON};
+use crate::engine::Calculator;
+use crate::format::{Notation, NumberFormat};
+use crate::locale::Locale;
+
+fn format(locale: Locale) -> NumberFormat {
+    NumberFormat {
+        locale,
+        ..NumberFormat::default()
+    }
+}
+
+#[test]
+fn locale_from_tag() {
+    assert_eq!(Locale::from_tag("fr-FR"), Some(Locale::FrFr));
+    assert_eq!(Locale::from_tag("de_CH.UTF-8"), Some(Locale::DeCh));
+    assert_eq!(Locale::from_tag("en"), Some(Locale::EnUs));
+    assert_eq!(Locale::from_tag("fr-CA"), Some(Locale::FrFr));
+    assert_eq!(Locale::from_tag("ja-JP"), None);
+}
+
+#[test]
+fn formats_with_locale_separators() {
+    assert_eq!(
+        format(Locale::FrFr).format(-1_234_567.5),
+        "-1\u{202F}234\u{202F}567,5"
+    );
+    assert_eq!(format(Locale::DeDe).format(1234.5), "1.234,5");
+    assert_eq!(format(Locale::DeCh).format(1234.5), "1\u{2019}234.5");
+    assert_eq!(format(Locale::EnGb).format(1234.5), "1,234.5");
+
+    let sci = NumberFormat {
+        notation: Notation::Sci(2),
+        ..format(Locale::DeDe)
+    };
+    assert_eq!(sci.format(123_456.), "1,23e5");
+}
+
+#[test]
+fn parses_with_locale_separators() {
+    assert_eq!(
+        format(Locale::FrFr).parse("1\u{202F}234,5").ok(),
+        Some(1234.5)
+    );
+    assert_eq!(format(Locale::DeDe).parse("1.234,5").ok(), Some(1234.5));
+    assert_eq!(format(Locale::DeDe).parse("1,5e-7").ok(), Some(1.5e-7));
+    assert_eq!(format(Locale::FrFr).parse("2.5").ok(), Some(2.5));
+    assert_eq!(format(Locale::DeCh).parse("2,5").ok(), Some(2.5));
+}
+
+#[test]
+fn typed_separators_follow_locale() {
+    let mut app = TestApp::new();
+
+    // The group separator is rejected, rather than dropped when parsed
+    app.type_text("2,5");
+    assert!(!app.display().contains([',', '.']));
+    assert_eq!(app.operation().left_operand(), Some(25.));
+
+    app.click("C");
+    app.type_text("2.5");
+    assert!(app.display().ends_with("2.5"));
+    assert_eq!(app.operation().left_operand(), Some(2.5));
+
+    app.set_format(format(Locale::DeDe));
+    app.click("C");
+    app.type_text("2.5");
+    assert!(!app.display().contains([',', '.']));
+    assert_eq!(app.operation().left_operand(), Some(25.));
+
+    app.click("C");
+    app.type_text("2,5");
+    assert!(app.display().ends_with("2,5"));
+    assert_eq!(app.operation().left_operand(), Some(2.5));
+
+    // The other separator is read as the decimal one, when not the group separator
+    app.set_format(format(Locale::FrFr));
+    app.click("C");
+    app.type_text("2.5");
+    assert!(app.display().ends_with("2,5"));
+    assert_eq!(app.operation().left_operand(), Some(2.5));
+
+    app.set_format(format(Locale::DeCh));
+    app.click("C");
+    app.type_text("2,5");
+    assert!(app.display().ends_with("2.5"));
+    assert_eq!(app.operation().left_operand(), Some(2.5));
+}
+
+#[test]
+fn engine_inserts_locale_decimal_separator() {
+    let mut calculator = Calculator::new(format(Locale::FrFr));
+
+    for button in [ONE_BUTTON, DOT_BUTTON, FIVE_BUTTON, DOT_BUTTON] {
+        calculator.press(button).expect("Valid button");
+    }
+    assert_eq!(calculator.display(), "1,5");
+
+    calculator.press(ADD_BUTTON).expect("Valid button");
+    calculator.press(ONE_BUTTON).expect("Valid button");
+    calculator.press(EQUAL_BUTTON).expect("Valid button");
+    assert_eq!(calculator.display(), "2,5");
+}
+
+#[test]
+fn decimal_button_follows_locale() {
+    let mut app = TestApp::new();
+    let dot = app.button(DOT_BUTTON);
+    assert_eq!(app.label(dot), ".");
+
+    app.set_format(format(Locale::DeDe));
+    assert_eq!(app.label(dot), ",");
+
+    app.click_all(&[TWO_BUTTON, DOT_BUTTON, FIVE_BUTTON]);
+    assert_eq!(app.display(), "2,5");
+    assert_eq!(app.operation().left_operand(), Some(2.5));
+}
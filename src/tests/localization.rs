@@ -0,0 +1,134 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::a11y::AccessibilityNode;
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::action::CalcAction;
+use crate::expression_line::ExpressionLine;
+use crate::format::NumberFormat;
+use crate::locale::Locale;
+use crate::localization::{Localization, PRESETS, UiLanguage};
+use crate::settings::{LanguageRow, Setting, SettingRow, SettingsIndicator};
+
+fn french(app: &TestApp) -> NumberFormat {
+    NumberFormat {
+        locale: Locale::FrFr,
+        precision: Some(2),
+        ..app.format()
+    }
+}
+
+#[test]
+fn presets_translate_every_message() {
+    let (_, english) = PRESETS[0];
+    let ids: Vec<&str> = english
+        .lines()
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(id, _)| id.trim())
+        .collect();
+    assert!(ids.contains(&"app-title"));
+
+    for (language, translations) in PRESETS {
+        let localization = Localization::new(language, translations).unwrap();
+        for id in &ids {
+            assert!(
+                translations.contains(&format!("\n{id} = ")),
+                "'{id}' is not translated in '{language}'"
+            );
+            assert_ne!(localization.text(id), *id);
+        }
+    }
+}
+
+#[test]
+fn missing_messages_fall_back_to_english() {
+    let localization = Localization::new("fr", "app-title = Calculatrice").unwrap();
+    assert_eq!(localization.text("app-title"), "Calculatrice");
+    assert_eq!(localization.text("button-add"), "add");
+    assert_eq!(localization.text("unknown-message"), "unknown-message");
+
+    // Unknown languages use the English translations
+    assert_eq!(Localization::preset("ja").language(), "en");
+}
+
+#[test]
+fn language_follows_the_locale() {
+    let mut app = TestApp::new();
+    let add = app.button("+");
+    let indicator = app.find::<SettingsIndicator>(|_| true);
+    assert_eq!(app.get::<AccessibilityNode>(add).label(), Some("add"));
+
+    let format = french(&app);
+    app.set_format(format);
+    assert_eq!(app.get::<AccessibilityNode>(add).label(), Some("ajouter"));
+    assert_eq!(app.get::<Text>(indicator).0, "AUTO 2DÉC DEMI-PAIR");
+}
+
+#[test]
+fn locale_is_switched_in_the_settings() {
+    let mut app = TestApp::new();
+    let locale = app.find::<SettingRow>(|row| row.0 == Setting::Locale);
+    assert_eq!(app.label(locale), "Number format");
+
+    app.set_format(NumberFormat {
+        locale: Locale::EnGb,
+        ..app.format()
+    });
+    app.click_entity(locale);
+    assert_eq!(app.format().locale, Locale::FrFr);
+    assert_eq!(app.label(locale), "Format des nombres");
+}
+
+#[test]
+fn language_is_independent_of_the_locale() {
+    let mut app = TestApp::new();
+    let add = app.button("+");
+    let indicator = app.find::<SettingsIndicator>(|_| true);
+    let language = app.find::<LanguageRow>(|_| true);
+    assert_eq!(app.resource::<UiLanguage>().0, None);
+
+    // English texts, with German numbers
+    app.set_format(NumberFormat {
+        locale: Locale::DeDe,
+        precision: Some(2),
+        ..app.format()
+    });
+    assert_eq!(app.label(language), "Sprache");
+    app.click_entity(language);
+    assert_eq!(app.resource::<UiLanguage>().0, Some("en"));
+    assert_eq!(app.label(language), "Language");
+    assert_eq!(app.get::<AccessibilityNode>(add).label(), Some("add"));
+    assert_eq!(app.get::<Text>(indicator).0, "AUTO 2DP HALF-EVEN");
+
+    app.type_text("2,5");
+    assert!(app.display().ends_with("2,5"));
+
+    // Back to the language of the locale after the last one
+    for _ in 1..PRESETS.len() {
+        app.click_entity(language);
+    }
+    assert_eq!(app.resource::<UiLanguage>().0, Some("pt"));
+    app.click_entity(language);
+    assert_eq!(app.resource::<UiLanguage>().0, None);
+    assert_eq!(app.get::<AccessibilityNode>(add).label(), Some("plus"));
+}
+
+#[test]
+fn calculation_errors_are_reported() {
+    let mut app = TestApp::new();
+    let line = app.find::<ExpressionLine>(|_| true);
+
+    app.write_message(CalcAction::Evaluate("12 +".to_string()));
+    assert_eq!(app.get::<Text>(line).0, "Unable to calculate");
+
+    let format = french(&app);
+    app.set_format(format);
+    app.write_message(CalcAction::Evaluate("12 +".to_string()));
+    assert_eq!(app.get::<Text>(line).0, "Calcul impossible");
+}
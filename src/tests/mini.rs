@@ -0,0 +1,114 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowLevel};
+
+use super::TestApp;
+use crate::button::CalcButton;
+use crate::layout::ButtonLayout;
+use crate::mini::parse_position;
+use crate::operation::CalcOperator;
+use crate::title_bar::{WindowAction, WindowControl};
+
+#[test]
+fn mini_mode_shows_the_recent_operators() {
+    let mut app = TestApp::new();
+    app.click_all(&["6", "/", "2", "-", "1", "="]);
+
+    let mini = app.find::<WindowControl>(|control| control.0 == WindowAction::Mini);
+    app.click_entity(mini);
+    assert_eq!(app.count::<CalcButton>(), 5);
+
+    let minus = app.button("-");
+    let divide = app.button("/");
+    let equal = app.button("=");
+    assert_eq!(
+        app.get::<Node>(minus).grid_column,
+        GridPlacement::start_span(1, 1)
+    );
+    assert_eq!(
+        app.get::<Node>(divide).grid_column,
+        GridPlacement::start_span(2, 1)
+    );
+    assert_eq!(
+        app.get::<Node>(equal).grid_column,
+        GridPlacement::start_span(5, 1)
+    );
+
+    // The display is kept
+    assert_eq!(app.display(), "5");
+    app.click("*");
+    assert_eq!(app.operation().left_operand(), Some(5.));
+    assert_eq!(app.operation().operator(), Some(CalcOperator::Mul));
+
+    app.click_entity(mini);
+    assert_eq!(app.count::<CalcButton>(), 19);
+    app.click_all(&["7", "="]);
+    assert_eq!(app.display(), "35");
+}
+
+#[test]
+fn mini_mode_pins_the_window() {
+    let mut app = TestApp::new();
+    let window = app.spawn((Window::default(), PrimaryWindow));
+    let standard_size = app.get::<Window>(window).resolution.size();
+    let mini = app.find::<WindowControl>(|control| control.0 == WindowAction::Mini);
+
+    app.click_entity(mini);
+    let mut pinned = app.get::<Window>(window);
+    assert_eq!(pinned.window_level, WindowLevel::AlwaysOnTop);
+    assert!(pinned.resolution.height() < standard_size.y);
+
+    // Moved while in mini mode
+    pinned.position = WindowPosition::At(IVec2::new(100, 200));
+    app.insert(window, pinned);
+
+    app.click_entity(mini);
+    let standard = app.get::<Window>(window);
+    assert_eq!(standard.window_level, WindowLevel::Normal);
+    assert_eq!(standard.resolution.size(), standard_size);
+
+    // The position is remembered
+    app.click_entity(mini);
+    assert_eq!(
+        app.get::<Window>(window).position,
+        WindowPosition::At(IVec2::new(100, 200))
+    );
+}
+
+#[test]
+fn layout_reloaded_in_mini_mode_is_restored() {
+    let mut app = TestApp::new();
+    let mini = app.find::<WindowControl>(|control| control.0 == WindowAction::Mini);
+    app.click_entity(mini);
+
+    // As when the layout file is modified
+    let layout = ButtonLayout::parse(
+        r#"(
+            name: "custom",
+            rows: 1,
+            columns: 2,
+            buttons: [(action: "1", row: 0, column: 0), (action: "=", row: 0, column: 1)],
+        )"#,
+    )
+    .expect("Valid layout");
+    app.set_layout(layout);
+    assert_eq!(app.count::<CalcButton>(), 5);
+
+    app.click_entity(mini);
+    assert_eq!(app.count::<CalcButton>(), 2);
+    app.click_entity(mini);
+    assert_eq!(app.count::<CalcButton>(), 5);
+}
+
+#[test]
+fn saved_position_is_parsed() {
+    assert_eq!(parse_position("100 -200\n"), Some(IVec2::new(100, -200)));
+    assert_eq!(parse_position("100"), None);
+    assert_eq!(parse_position("x y"), None);
+}
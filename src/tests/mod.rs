@@ -0,0 +1,277 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+//! Headless test harness: the calculator scene and systems run without any window nor GPU,
+//! driven by simulated clicks and key presses.
+
+mod accessibility;
+mod buttons;
+mod click;
+mod clipboard;
+mod format;
+mod history;
+mod keyboard;
+mod layout;
+mod locale;
+mod localization;
+mod mini;
+mod properties;
+#[cfg(not(target_arch = "wasm32"))]
+mod recorder;
+mod rounding;
+mod speech;
+mod tape;
+mod theme;
+mod title_bar;
+mod viewport;
+
+use bevy::camera::NormalizedRenderTarget;
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKeyCode};
+use bevy::input_focus::InputFocus;
+use bevy::picking::backend::HitData;
+use bevy::picking::pointer::{Location, PointerButton, PointerId};
+use bevy::prelude::*;
+use bevy::text::EditableText;
+use std::time::Duration;
+
+use crate::CalculatorPlugin;
+use crate::button::{ButtonAction, CalcButton};
+use crate::format::NumberFormat;
+use crate::layout::ButtonLayout;
+use crate::operation::OperationMetadata;
+use crate::recorder::SETTLE_FRAMES;
+
+/// A headless calculator app.
+pub struct TestApp(App);
+
+impl TestApp {
+    /// Build the app and spawn the calculator scene.
+    pub fn new() -> Self {
+        let mut app = CalculatorPlugin::headless_app();
+
+        // Run the startup systems, then let the scene and the buttons of the layout spawn
+        app.update();
+        app.update();
+        app.update();
+
+        Self(app)
+    }
+
+    /// Simulate a click on the button with the given label.
+    pub fn click(&mut self, label: &str) {
+        let entity = self.button(label);
+        self.click_entity(entity);
+    }
+
+    /// Simulate a click on any clickable entity, e.g. a row of the settings panel.
+    pub fn click_entity(&mut self, entity: Entity) {
+        let hit = HitData::new(Entity::PLACEHOLDER, 0., None, None);
+        let location = Location {
+            target: NormalizedRenderTarget::None {
+                width: 0,
+                height: 0,
+            },
+            position: Vec2::ZERO,
+        };
+        let click = Click {
+            button: PointerButton::Primary,
+            hit,
+            duration: Duration::ZERO,
+        };
+
+        self.0
+            .world_mut()
+            .trigger(Pointer::new(PointerId::Mouse, location, click, entity));
+        self.settle();
+    }
+
+    /// Simulate a click on each button, in order.
+    pub fn click_all(&mut self, labels: &[&str]) {
+        for label in labels {
+            self.click(label);
+        }
+    }
+
+    /// Simulate a key press, then its release on the next frame.
+    pub fn press_key(&mut self, key_code: KeyCode, logical_key: Key) {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            self.0.world_mut().write_message(KeyboardInput {
+                key_code,
+                logical_key: logical_key.clone(),
+                state,
+                text: None,
+                repeat: false,
+                window: Entity::PLACEHOLDER,
+            });
+            self.0.update();
+        }
+        self.settle();
+    }
+
+    /// Simulate typing a text in the display.
+    pub fn type_text(&mut self, text: &str) {
+        let world = self.0.world_mut();
+        let display = world
+            .query_filtered::<Entity, With<OperationMetadata>>()
+            .single(world)
+            .expect("The display should be spawned");
+        world.resource_mut::<InputFocus>().set(display);
+
+        self.type_keys(text);
+    }
+
+    /// Simulate typing a text in the focused entity, e.g. the note editor of the tape.
+    pub fn type_keys(&mut self, text: &str) {
+        for c in text.chars() {
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                self.0.world_mut().write_message(KeyboardInput {
+                    key_code: KeyCode::Unidentified(NativeKeyCode::Unidentified),
+                    logical_key: Key::Character(c.to_string().into()),
+                    state,
+                    text: (state == ButtonState::Pressed).then(|| c.to_string().into()),
+                    repeat: false,
+                    window: Entity::PLACEHOLDER,
+                });
+                self.0.update();
+            }
+        }
+        self.settle();
+    }
+
+    /// Send a message to the app, e.g. a text pasted from the clipboard.
+    pub fn write_message<M: Message>(&mut self, message: M) {
+        self.0.world_mut().write_message(message);
+        self.settle();
+    }
+
+    /// Send several messages to the app, and run a single frame.
+    pub fn write_messages<M: Message>(&mut self, messages: impl IntoIterator<Item = M>) {
+        for message in messages {
+            self.0.world_mut().write_message(message);
+        }
+        self.0.update();
+    }
+
+    /// Remove the focus from any button, as before the first click.
+    pub fn clear_focus(&mut self) {
+        self.0.world_mut().resource_mut::<InputFocus>().clear();
+        self.settle();
+    }
+
+    /// Current value of the calculator display.
+    pub fn display(&mut self) -> String {
+        let world = self.0.world_mut();
+        world
+            .query_filtered::<&EditableText, With<OperationMetadata>>()
+            .single(world)
+            .expect("The display should be spawned")
+            .value()
+            .to_string()
+    }
+
+    /// Current state of the ongoing operation.
+    pub fn operation(&mut self) -> OperationMetadata {
+        let world = self.0.world_mut();
+        world
+            .query::<&OperationMetadata>()
+            .single(world)
+            .expect("The display should be spawned")
+            .clone()
+    }
+
+    /// Find the button applying the given button variant.
+    pub fn button(&mut self, label: &str) -> Entity {
+        let world = self.0.world_mut();
+        world
+            .query_filtered::<(Entity, &ButtonAction), With<CalcButton>>()
+            .iter(world)
+            .find(|(_, action)| action.0 == label)
+            .map(|(entity, _)| entity)
+            .unwrap_or_else(|| panic!("No button labelled '{label}'"))
+    }
+
+    /// Find the entity with a component matching the predicate.
+    pub fn find<C: Component>(&mut self, predicate: impl Fn(&C) -> bool) -> Entity {
+        let world = self.0.world_mut();
+        world
+            .query::<(Entity, &C)>()
+            .iter(world)
+            .find(|(_, component)| predicate(component))
+            .map(|(entity, _)| entity)
+            .expect("No entity matching the predicate")
+    }
+
+    /// Number of entities with the component, e.g. the buttons.
+    pub fn count<C: Component>(&mut self) -> usize {
+        let world = self.0.world_mut();
+        world.query::<&C>().iter(world).count()
+    }
+
+    /// Visible label of a button.
+    pub fn label(&mut self, entity: Entity) -> String {
+        let world = self.0.world_mut();
+        let text = world
+            .get::<Children>(entity)
+            .expect("The button should have a label")[0];
+        world
+            .get::<Text>(text)
+            .expect("The button should have a label")
+            .0
+            .clone()
+    }
+
+    /// Current display format.
+    pub fn format(&self) -> NumberFormat {
+        self.0.world().resource::<NumberFormat>().clone()
+    }
+
+    /// Current state of a resource, e.g. the history.
+    pub fn resource<R: Resource + Clone>(&self) -> R {
+        self.0.world().resource::<R>().clone()
+    }
+
+    /// Change the display format, e.g. its locale.
+    pub fn set_format(&mut self, format: NumberFormat) {
+        self.0.insert_resource(format);
+        self.settle();
+    }
+
+    /// Change the button layout, as when its file is modified.
+    pub fn set_layout(&mut self, layout: ButtonLayout) {
+        self.0.insert_resource(layout);
+        self.settle();
+    }
+
+    /// Spawn an entity, e.g. the window, absent from the headless app.
+    pub fn spawn(&mut self, bundle: impl Bundle) -> Entity {
+        let entity = self.0.world_mut().spawn(bundle).id();
+        self.settle();
+        entity
+    }
+
+    /// Replace a component of an entity, e.g. to move the window.
+    pub fn insert<C: Component>(&mut self, entity: Entity, component: C) {
+        self.0.world_mut().entity_mut(entity).insert(component);
+        self.settle();
+    }
+
+    /// Read a component of an entity, e.g. the colors of a button.
+    pub fn get<C: Component + Clone>(&self, entity: Entity) -> C {
+        self.0
+            .world()
+            .get::<C>(entity)
+            .expect("The entity should have the component")
+            .clone()
+    }
+
+    fn settle(&mut self) {
+        for _ in 0..SETTLE_FRAMES {
+            self.0.update();
+        }
+    }
+}
@@ -0,0 +1,200 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use proptest::prelude::*;
+
+use super::TestApp;
+use crate::button::{ALL_BUTTONS, ButtonVariant, CLEAR_BUTTON, INVERT_BUTTON, POURCENT_BUTTON};
+use crate::engine::{self, Calculator, DisplayEdit};
+use crate::expression;
+use crate::format::NumberFormat;
+use crate::operation::{CalcOperator, OperationMetadata};
+
+/// Finite values, as typed on the calculator (no exponent notation).
+fn operand() -> impl Strategy<Value = f64> {
+    (-1_000_000_i64..1_000_000, 0_u32..4).prop_map(|(mantissa, scale)| {
+        #[allow(clippy::cast_precision_loss)]
+        let mantissa = mantissa as f64;
+        mantissa / 10_f64.powi(scale.cast_signed())
+    })
+}
+
+fn button() -> impl Strategy<Value = ButtonVariant> {
+    proptest::sample::select(ALL_BUTTONS.to_vec())
+}
+
+fn calculate(left: f64, operator: CalcOperator, right: f64) -> f64 {
+    let mut op_metadata = OperationMetadata::default();
+    op_metadata.set_left_operand(left);
+    op_metadata.set_operator(operator);
+    op_metadata.set_operand(right);
+
+    op_metadata.calculate().expect("Complete operation")
+}
+
+/// Apply a button on a display value, returning the new display value.
+fn apply(button: ButtonVariant, display: &str) -> String {
+    let mut op_metadata = OperationMetadata::default();
+    let format = NumberFormat::default();
+    match engine::apply_button(button, display, &mut op_metadata, &format)
+        .expect("Valid button action")
+    {
+        DisplayEdit::Keep => display.to_string(),
+        DisplayEdit::Insert(text) => format!("{display}{text}"),
+        DisplayEdit::Replace(text) => text,
+        DisplayEdit::Result(evaluation) => format.format(evaluation.value),
+    }
+}
+
+/// Reference arithmetic expression, evaluated directly.
+#[derive(Clone, Debug)]
+enum Expr {
+    Number(f64),
+    Neg(Box<Self>),
+    Percent(Box<Self>),
+    Binary(Box<Self>, char, Box<Self>),
+}
+
+impl Expr {
+    fn eval(&self) -> f64 {
+        match self {
+            Self::Number(value) => *value,
+            Self::Neg(expr) => -expr.eval(),
+            Self::Percent(expr) => expr.eval() / 100.0,
+            Self::Binary(lhs, operator, rhs) => {
+                let (lhs, rhs) = (lhs.eval(), rhs.eval());
+                match operator {
+                    '+' => lhs + rhs,
+                    '-' => lhs - rhs,
+                    '*' => lhs * rhs,
+                    _ => lhs / rhs,
+                }
+            }
+        }
+    }
+
+    /// Fully parenthesized, so the parser must follow the same evaluation order.
+    fn render(&self) -> String {
+        match self {
+            Self::Number(value) => value.to_string(),
+            Self::Neg(expr) => format!("-({})", expr.render()),
+            Self::Percent(expr) => format!("({})%", expr.render()),
+            Self::Binary(lhs, operator, rhs) => {
+                format!("({}) {operator} ({})", lhs.render(), rhs.render())
+            }
+        }
+    }
+}
+
+fn expr() -> impl Strategy<Value = Expr> {
+    // Signs are only generated by `Neg`, as the parser reads them as unary operators
+    let leaf = operand().prop_map(|value| Expr::Number(value.abs()));
+    leaf.prop_recursive(4, 32, 2, |inner| {
+        prop_oneof![
+            inner.clone().prop_map(|expr| Expr::Neg(Box::new(expr))),
+            inner.clone().prop_map(|expr| Expr::Percent(Box::new(expr))),
+            (
+                inner.clone(),
+                proptest::sample::select(vec!['+', '-', '*', '/']),
+                inner
+            )
+                .prop_map(|(lhs, operator, rhs)| Expr::Binary(
+                    Box::new(lhs),
+                    operator,
+                    Box::new(rhs)
+                )),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn add_and_mul_are_commutative(a in operand(), b in operand()) {
+        for operator in [CalcOperator::Add, CalcOperator::Mul] {
+            prop_assert_eq!(calculate(a, operator, b), calculate(b, operator, a));
+        }
+    }
+
+    #[test]
+    fn sub_itself_is_zero(x in operand()) {
+        prop_assert_eq!(calculate(x, CalcOperator::Sub, x), 0.0);
+    }
+
+    #[test]
+    fn invert_twice_is_identity(display in "-?[1-9][0-9]{0,8}(\\.[0-9]{1,4})?") {
+        prop_assert_eq!(apply(INVERT_BUTTON, &apply(INVERT_BUTTON, &display)), display);
+    }
+
+    #[test]
+    fn percent_divides_by_hundred(x in operand()) {
+        let format = NumberFormat::default();
+        let display = apply(POURCENT_BUTTON, &x.to_string());
+        prop_assert_eq!(format.parse(&display).ok(), Some(x / 100.0));
+    }
+
+    #[test]
+    fn displayed_result_round_trips(a in operand(), b in operand(), grouping: bool) {
+        let format = NumberFormat { grouping, ..NumberFormat::default() };
+        let value = calculate(a, CalcOperator::Div, b);
+        let display = format.format(value);
+        let reparsed = format.parse(&display).expect("Displayed result should parse");
+
+        prop_assert!(display.chars().count() <= format.max_width, "{} is too wide", display);
+        prop_assert!(
+            (reparsed - value).abs() <= value.abs() * 1e-11
+                || reparsed == value
+                || (reparsed.is_nan() && value.is_nan()),
+            "{} displayed as {}", value, display
+        );
+    }
+
+    #[test]
+    fn parser_matches_reference(expr in expr()) {
+        let expected = expr.eval();
+        let actual = expression::evaluate(&expr.render()).expect("Rendered expression should parse");
+        prop_assert!(
+            actual == expected || (actual.is_nan() && expected.is_nan()),
+            "{} = {} instead of {}", expr.render(), actual, expected
+        );
+    }
+
+    /// Fuzz the engine with arbitrary button sequences: it must never panic, and its results must be displayed.
+    #[test]
+    fn engine_handles_any_button_sequence(buttons in proptest::collection::vec(button(), 0..64)) {
+        let mut calculator = Calculator::default();
+
+        for button in buttons {
+            if let Ok(Some(evaluation)) = calculator.press(button) {
+                prop_assert_eq!(calculator.display(), NumberFormat::default().format(evaluation.value));
+            }
+        }
+
+        calculator.press(CLEAR_BUTTON).expect("Clear never fails");
+        prop_assert_eq!(calculator.display(), "0");
+        prop_assert!(!calculator.operation().is_under_operation());
+    }
+}
+
+proptest! {
+    // Each case runs a headless app, keep them few
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    /// The GUI and the engine used by the other frontends must behave identically, including on
+    /// non-finite values (e.g. a division by zero).
+    #[test]
+    fn gui_matches_engine(buttons in proptest::collection::vec(button(), 0..12)) {
+        let mut app = TestApp::new();
+        let mut calculator = Calculator::default();
+
+        for button in buttons {
+            app.click(button);
+            let _ = calculator.press(button);
+        }
+
+        prop_assert_eq!(app.display(), calculator.display());
+    }
+}
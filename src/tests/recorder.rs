@@ -0,0 +1,42 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use std::fs;
+
+use crate::button::{DIVIDE_BUTTON, EQUAL_BUTTON, ONE_BUTTON, THREE_BUTTON};
+use crate::recorder::replay_headless;
+
+/// Replay an input log dividing 1 by 3, recorded with the given display format settings.
+fn replay(name: &str, settings: &str, expected: &str) -> i32 {
+    let path = std::env::temp_dir().join(format!("calculator-{name}.log"));
+    let log = format!(
+        "# calculator-gui-rs input log\n\
+         0.000\tformat\t{settings}\n\
+         0.100\tpress\t{ONE_BUTTON}\n\
+         0.200\tpress\t{DIVIDE_BUTTON}\n\
+         0.300\tpress\t{THREE_BUTTON}\n\
+         0.400\tpress\t{EQUAL_BUTTON}\n\
+         0.500\tend\t{expected}\n"
+    );
+    fs::write(&path, log).expect("Writable input log");
+
+    let code = replay_headless(&path.to_string_lossy());
+    let _ = fs::remove_file(&path);
+    code
+}
+
+#[test]
+fn recorded_format_is_replayed() {
+    let settings = "locale=fr-FR precision=2 notation=auto rounding=half-even input-length=16";
+    assert_eq!(replay("recorded-format", settings, "0,33"), 0);
+
+    // The display differs with other settings
+    let settings = "locale=en-US precision=off notation=auto rounding=half-even input-length=16";
+    assert_eq!(replay("other-format", settings, "0,33"), 1);
+
+    assert_eq!(replay("invalid-format", "locale=xx-XX", "0,33"), 2);
+}
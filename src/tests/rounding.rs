@@ -0,0 +1,87 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::button::{DIVIDE_BUTTON, EQUAL_BUTTON, ONE_BUTTON, POURCENT_BUTTON, THREE_BUTTON};
+use crate::engine::Calculator;
+use crate::format::{NumberFormat, RoundingMode};
+use crate::settings::{Setting, SettingRow, SettingsIndicator, SettingsPanel};
+
+#[test]
+fn rounding_modes() {
+    let cases = [
+        (RoundingMode::HalfEven, [2., 2., -2., 1.]),
+        (RoundingMode::HalfUp, [3., 2., -3., 1.01]),
+        (RoundingMode::HalfDown, [2., 1., -2., 1.]),
+        (RoundingMode::TowardZero, [2., 1., -2., 1.]),
+        (RoundingMode::Floor, [2., 1., -3., 1.]),
+        (RoundingMode::Ceiling, [3., 2., -2., 1.01]),
+    ];
+
+    for (mode, [even_tie, odd_tie, negative_tie, decimal_tie]) in cases {
+        assert_eq!(mode.round(2.5, 0), even_tie, "{mode}");
+        assert_eq!(mode.round(1.5, 0), odd_tie, "{mode}");
+        assert_eq!(mode.round(-2.5, 0), negative_tie, "{mode}");
+        // 1.005 is stored as 1.00499999999999989..., but is still a tie
+        assert_eq!(mode.round(1.005, 2), decimal_tie, "{mode}");
+    }
+}
+
+#[test]
+fn rounding_mode_round_trips() {
+    for mode in RoundingMode::ALL {
+        assert_eq!(mode.to_string().parse::<RoundingMode>().ok(), Some(mode));
+    }
+    assert_eq!(
+        "truncate".parse::<RoundingMode>().ok(),
+        Some(RoundingMode::TowardZero)
+    );
+}
+
+#[test]
+fn results_are_rounded_to_precision() {
+    let mut calculator = Calculator::new(NumberFormat {
+        precision: Some(2),
+        ..NumberFormat::default()
+    });
+
+    for button in [ONE_BUTTON, DIVIDE_BUTTON, THREE_BUTTON, EQUAL_BUTTON] {
+        calculator.press(button).expect("Valid button");
+    }
+    assert_eq!(calculator.display(), "0.33");
+
+    calculator.press(POURCENT_BUTTON).expect("Valid button");
+    assert_eq!(calculator.display(), "0");
+}
+
+#[test]
+fn settings_panel_switches_the_settings() {
+    let mut app = TestApp::new();
+    let indicator = app.find::<SettingsIndicator>(|_| true);
+    let panel = app.find::<SettingsPanel>(|_| true);
+    assert_eq!(app.get::<Text>(indicator).0, "AUTO");
+    assert_eq!(app.get::<Node>(panel).display, Display::None);
+
+    app.click_entity(indicator);
+    assert_eq!(app.get::<Node>(panel).display, Display::Flex);
+
+    let precision = app.find::<SettingRow>(|row| row.0 == Setting::Precision);
+    let rounding = app.find::<SettingRow>(|row| row.0 == Setting::Rounding);
+    app.click_entity(precision);
+    app.click_entity(precision);
+    app.click_entity(rounding);
+
+    let format = app.format();
+    assert_eq!(format.precision, Some(1));
+    assert_eq!(format.rounding, RoundingMode::HalfUp);
+    assert_eq!(app.get::<Text>(indicator).0, "AUTO 1DP HALF-UP");
+
+    app.click_entity(indicator);
+    assert_eq!(app.get::<Node>(panel).display, Display::None);
+}
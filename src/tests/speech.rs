@@ -0,0 +1,74 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::input::keyboard::Key;
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::action::{CalcAction, CalcApplied};
+use crate::engine::Evaluation;
+use crate::format::NumberFormat;
+use crate::localization::Localization;
+use crate::speech::{VoiceFeedback, typed_utterance, utterance};
+
+fn applied(action: CalcAction, value: Option<f64>) -> CalcApplied {
+    CalcApplied {
+        action,
+        display: "0".to_string(),
+        evaluation: value.map(|value| Evaluation {
+            expression: String::new(),
+            value,
+        }),
+    }
+}
+
+#[test]
+fn key_presses_and_results_are_said() {
+    let format = NumberFormat::default();
+    let localization = Localization::default();
+
+    for (applied, expected) in [
+        (applied(CalcAction::Press("7"), None), Some("7")),
+        (applied(CalcAction::Press("*"), None), Some("multiply")),
+        (
+            applied(CalcAction::Press("="), Some(1234.5)),
+            Some("equals, 1,234.5"),
+        ),
+        (
+            applied(CalcAction::Evaluate("2*3".to_string()), Some(6.)),
+            Some("6"),
+        ),
+    ] {
+        assert_eq!(
+            utterance(&applied, &format, &localization).as_deref(),
+            expected
+        );
+    }
+}
+
+#[test]
+fn typed_keys_are_said() {
+    let localization = Localization::default();
+
+    assert_eq!(typed_utterance("7", &localization), "7");
+    assert_eq!(
+        typed_utterance("-1.5", &localization),
+        "subtract, 1, decimal point, 5"
+    );
+}
+
+#[test]
+fn voice_feedback_is_toggled() {
+    let mut app = TestApp::new();
+    assert!(!app.resource::<VoiceFeedback>().enabled);
+
+    app.press_key(KeyCode::F3, Key::F3);
+    assert!(app.resource::<VoiceFeedback>().enabled);
+
+    app.press_key(KeyCode::F3, Key::F3);
+    assert!(!app.resource::<VoiceFeedback>().enabled);
+}
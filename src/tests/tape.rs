@@ -0,0 +1,123 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::input::keyboard::Key;
+use bevy::prelude::KeyCode;
+
+use super::TestApp;
+use crate::clipboard::Paste;
+use crate::format::NumberFormat;
+use crate::tape::{Tape, TapeFormat, TapeLineRow, TapeMark};
+
+fn lines(app: &TestApp) -> Vec<(String, TapeMark)> {
+    app.resource::<Tape>()
+        .lines()
+        .iter()
+        .map(|line| (line.entry.clone(), line.mark))
+        .collect()
+}
+
+fn line(entry: &str, mark: TapeMark) -> (String, TapeMark) {
+    (entry.to_string(), mark)
+}
+
+#[test]
+fn entries_and_operators_are_logged() {
+    let mut app = TestApp::new();
+
+    app.click_all(&["1", "2", "+", "7", "=", "C"]);
+    // The second operator replaces the first one
+    app.click_all(&["5", "+", "-", "3", "=", "*", "4", "="]);
+    app.write_message(Paste("2*3".to_string()));
+
+    assert_eq!(
+        lines(&app),
+        [
+            line("12", TapeMark::Add),
+            line("7", TapeMark::Equals),
+            line("19", TapeMark::Subtotal),
+            line("19", TapeMark::Total),
+            line("5", TapeMark::Sub),
+            line("3", TapeMark::Equals),
+            line("2", TapeMark::Subtotal),
+            line("2", TapeMark::Mul),
+            line("4", TapeMark::Equals),
+            line("8", TapeMark::Subtotal),
+            line("2*3", TapeMark::Expression),
+            line("6", TapeMark::Subtotal),
+        ]
+    );
+    assert_eq!(app.count::<TapeLineRow>(), 12);
+
+    // Clearing an ongoing operation
+    app.click_all(&["9", "+", "C"]);
+    assert_eq!(
+        lines(&app)[12..],
+        [line("9", TapeMark::Add), line("", TapeMark::Clear)]
+    );
+}
+
+#[test]
+fn lines_are_selected_for_their_note() {
+    let mut app = TestApp::new();
+    app.click_all(&["1", "+", "2", "="]);
+
+    let row = app.find::<TapeLineRow>(|row| row.0 == 1);
+    app.click_entity(row);
+    assert_eq!(app.resource::<Tape>().selected(), Some(1));
+
+    let mut tape = app.resource::<Tape>();
+    tape.set_note(1, &"x".repeat(50)).unwrap();
+    assert_eq!(tape.lines()[1].note.len(), 40);
+    assert!(tape.set_note(3, "").is_err());
+}
+
+#[test]
+fn notes_are_typed_in_the_editor() {
+    let mut app = TestApp::new();
+    app.click_all(&["1", "+", "2", "=", "3", "+"]);
+
+    let row = app.find::<TapeLineRow>(|row| row.0 == 1);
+    app.click_entity(row);
+    app.type_keys("Rent");
+    assert_eq!(app.resource::<Tape>().lines()[1].note, "Rent");
+
+    // Only the note of the row is updated, the lines aren't listed again
+    assert_eq!(app.find::<TapeLineRow>(|row| row.0 == 1), row);
+    assert_eq!(app.label(row), "Rent");
+
+    // Enter doesn't reach the calculator
+    app.press_key(KeyCode::Enter, Key::Enter);
+    assert_eq!(app.display(), "3");
+    assert!(app.operation().is_under_operation());
+}
+
+#[test]
+fn tape_is_exported() {
+    let mut app = TestApp::new();
+    app.click_all(&["1", "2", "+", "7", "=", "C"]);
+
+    let mut tape = app.resource::<Tape>();
+    tape.set_note(0, "Rent, \"May\" & <fees>").unwrap();
+    let format = NumberFormat::default();
+
+    assert_eq!(
+        tape.export(TapeFormat::Text, &format),
+        "12 +  Rent, \"May\" & <fees>\n 7 =\n19 S\n19 T\n"
+    );
+    assert_eq!(
+        tape.export(TapeFormat::Csv, &format),
+        "entry,mark,note\n12,+,\"Rent, \"\"May\"\" & <fees>\"\n7,=,\n19,S,\n19,T,\n"
+    );
+
+    let html = tape.export(TapeFormat::Html, &format);
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains(
+        "<tr><td class=\"entry\">12</td><td>+</td><td>Rent, &quot;May&quot; &amp; &lt;fees&gt;</td></tr>"
+    ));
+    assert!(html.contains("<tr><td class=\"entry\">19</td><td>T</td><td></td></tr>"));
+}
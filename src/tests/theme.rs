@@ -0,0 +1,83 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+
+use super::TestApp;
+use crate::settings::ThemeRow;
+use crate::theme::{Theme, ThemeRole};
+
+#[test]
+fn presets_are_valid() {
+    for name in ["dark", "light", "high-contrast"] {
+        let theme = Theme::preset(name).expect("Valid built-in theme");
+        assert_eq!(theme.name, name);
+    }
+
+    assert!(Theme::preset("solarized").is_err());
+}
+
+#[test]
+fn presets_cycle() {
+    let dark = Theme::default();
+    let light = dark.next();
+    assert_eq!(light.name, "light");
+    assert_eq!(light.next().name, "high-contrast");
+    assert_eq!(light.next().next(), dark);
+}
+
+#[test]
+fn custom_theme_colors_are_checked() {
+    let dark = include_str!("../../assets/themes/dark.ron");
+
+    let custom = Theme::parse(&dark.replace("\"dark\"", "\"custom\"")).expect("Valid theme");
+    assert_eq!(custom.name, "custom");
+    assert_eq!(custom.next().name, "dark");
+
+    assert!(Theme::parse(&dark.replace("#3366CC", "blue")).is_err());
+}
+
+#[test]
+fn theme_switches_live() {
+    let mut app = TestApp::new();
+    let seven = app.button("7");
+    let background = app.find::<ThemeRole>(|role| *role == ThemeRole::Background);
+
+    let theme_row = app.find::<ThemeRow>(|_| true);
+    app.click_entity(theme_row);
+
+    let light = Theme::preset("light").expect("Valid built-in theme");
+    assert_eq!(app.get::<BackgroundColor>(seven).0, light.digit.normal);
+    assert_eq!(app.get::<BackgroundColor>(background).0, light.background);
+}
+
+#[test]
+fn buttons_are_colored_by_kind() {
+    let mut app = TestApp::new();
+    let theme = Theme::default();
+
+    for (label, colors) in [
+        ("7", &theme.digit),
+        ("+", &theme.operator),
+        ("%", &theme.function),
+        ("C", &theme.clear),
+        ("=", &theme.equals),
+    ] {
+        let button = app.button(label);
+        assert_eq!(
+            app.get::<BackgroundColor>(button).0,
+            colors.normal,
+            "{label}"
+        );
+
+        let text = app.find::<ChildOf>(|child_of| child_of.parent() == button);
+        assert_eq!(app.get::<TextColor>(text).0, colors.text, "{label}");
+    }
+
+    assert_ne!(theme.equals, theme.digit);
+    assert_ne!(theme.operator, theme.digit);
+}
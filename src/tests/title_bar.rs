@@ -0,0 +1,34 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowLevel};
+
+use super::TestApp;
+use crate::theme::Theme;
+use crate::title_bar::{WindowAction, WindowControl};
+
+#[test]
+fn always_on_top_toggles() {
+    let mut app = TestApp::new();
+    let window = app.spawn((Window::default(), PrimaryWindow));
+    let always_on_top = app.find::<WindowControl>(|control| control.0 == WindowAction::AlwaysOnTop);
+
+    app.click_entity(always_on_top);
+    assert_eq!(
+        app.get::<Window>(window).window_level,
+        WindowLevel::AlwaysOnTop
+    );
+    assert_eq!(
+        app.get::<BackgroundColor>(always_on_top).0,
+        Theme::default().focus
+    );
+
+    app.click_entity(always_on_top);
+    assert_eq!(app.get::<Window>(window).window_level, WindowLevel::Normal);
+    assert_eq!(app.get::<BackgroundColor>(always_on_top).0, Color::NONE);
+}
@@ -0,0 +1,70 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use super::TestApp;
+use crate::button::{
+    DOT_BUTTON, EQUAL_BUTTON, MULTIPLY_BUTTON, NINE_BUTTON, ONE_BUTTON, SUB_BUTTON, ZERO_BUTTON,
+};
+use crate::format::NumberFormat;
+use crate::viewport::{MAX_FONT_SIZE, MIN_FONT_SIZE, fitted_font_size};
+
+#[test]
+fn font_shrinks_with_content() {
+    assert_eq!(fitted_font_size(240., 1), MAX_FONT_SIZE);
+    assert_eq!(fitted_font_size(240., 20), MAX_FONT_SIZE);
+    assert_eq!(fitted_font_size(240., 25), 16.);
+    assert_eq!(fitted_font_size(240., 80), MIN_FONT_SIZE);
+}
+
+#[test]
+fn input_is_limited_to_max_length() {
+    let mut app = TestApp::new();
+    app.set_format(NumberFormat {
+        max_input_length: 8,
+        ..NumberFormat::default()
+    });
+
+    app.click_all(&[ONE_BUTTON; 6]);
+    app.click_all(&[DOT_BUTTON, NINE_BUTTON, NINE_BUTTON, NINE_BUTTON]);
+    assert_eq!(app.display(), "111111.99");
+
+    // The limit applies to each operand
+    app.click_all(&[SUB_BUTTON, NINE_BUTTON, NINE_BUTTON]);
+    assert_eq!(app.display(), "99");
+}
+
+#[test]
+fn results_are_not_truncated() {
+    let mut app = TestApp::new();
+    app.set_format(NumberFormat {
+        max_input_length: 8,
+        ..NumberFormat::default()
+    });
+
+    app.click_all(&[ONE_BUTTON; 8]);
+    app.click_all(&[MULTIPLY_BUTTON, ONE_BUTTON, ZERO_BUTTON, EQUAL_BUTTON]);
+    assert_eq!(app.display(), "111,111,110");
+    assert_eq!(app.operation().left_operand(), Some(111_111_110.));
+
+    // Nor the exponent of the scientific notation
+    app.click_all(&[MULTIPLY_BUTTON, ONE_BUTTON]);
+    app.click_all(&[ZERO_BUTTON; 6]);
+    app.click(EQUAL_BUTTON);
+    assert_eq!(app.display(), "1.1111111e14");
+}
+
+#[test]
+fn typed_input_is_truncated() {
+    let format = NumberFormat {
+        max_input_length: 4,
+        ..NumberFormat::default()
+    };
+
+    assert_eq!(format.truncate_input("-12.34"), None);
+    assert_eq!(format.truncate_input("-12.345"), Some("-12.34".to_string()));
+    assert_eq!(format.truncate_input("123456"), Some("1234".to_string()));
+}
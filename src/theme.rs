@@ -0,0 +1,212 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+use crate::button::{ButtonCategory, ButtonKind};
+
+/// Built-in themes, by name.
+const PRESETS: [(&str, &str); 3] = [
+    ("dark", include_str!("../assets/themes/dark.ron")),
+    ("light", include_str!("../assets/themes/light.ron")),
+    (
+        "high-contrast",
+        include_str!("../assets/themes/high-contrast.ron"),
+    ),
+];
+
+/// Deserialize a color from an hexadecimal string, e.g. `#262626` or `#000000CC`.
+fn hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let hex = String::deserialize(deserializer)?;
+    Srgba::hex(&hex)
+        .map(Color::from)
+        .map_err(|err| D::Error::custom(format!("Invalid color '{hex}': {err}")))
+}
+
+/// Colors of a category of buttons.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct ButtonColors {
+    #[serde(deserialize_with = "hex")]
+    pub normal: Color,
+    #[serde(deserialize_with = "hex")]
+    pub hovered: Color,
+    #[serde(deserialize_with = "hex")]
+    pub pressed: Color,
+    #[serde(deserialize_with = "hex")]
+    pub text: Color,
+}
+
+/// Colors of the display.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct DisplayColors {
+    #[serde(deserialize_with = "hex")]
+    pub background: Color,
+    #[serde(deserialize_with = "hex")]
+    pub border: Color,
+    #[serde(deserialize_with = "hex")]
+    pub text: Color,
+    /// Smaller texts around the display value (expression line, indicators)
+    #[serde(deserialize_with = "hex")]
+    pub secondary_text: Color,
+}
+
+/// Colors of the calculator, loaded from a RON file (see the presets in `assets/themes`).
+#[derive(Resource, Deserialize, Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    /// Background behind the buttons
+    #[serde(deserialize_with = "hex")]
+    pub background: Color,
+    pub display: DisplayColors,
+    pub digit: ButtonColors,
+    pub operator: ButtonColors,
+    /// Buttons transforming the display value (`+/-`, `%`)
+    pub function: ButtonColors,
+    pub clear: ButtonColors,
+    /// Accent of the `=` button
+    pub equals: ButtonColors,
+    #[serde(deserialize_with = "hex")]
+    pub border: Color,
+    /// Border of the hovered button and of the current operator
+    #[serde(deserialize_with = "hex")]
+    pub highlight_border: Color,
+    /// Background of the button focused with the keyboard
+    #[serde(deserialize_with = "hex")]
+    pub focus: Color,
+    /// Border of the button focused with the keyboard
+    #[serde(deserialize_with = "hex")]
+    pub focus_ring: Color,
+    /// Background of the settings panel
+    #[serde(deserialize_with = "hex")]
+    pub panel: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::preset(PRESETS[0].0).expect("The built-in themes are valid")
+    }
+}
+
+impl Theme {
+    /// Parse a theme from its RON definition.
+    pub fn parse(ron: &str) -> Result<Self> {
+        Ok(ron::from_str(ron)?)
+    }
+
+    /// Get a built-in theme by name: `dark`, `light` or `high-contrast`.
+    pub fn preset(name: &str) -> Result<Self> {
+        let (_, ron) = PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .ok_or_else(|| format!("Unknown theme: '{name}'"))?;
+
+        Self::parse(ron)
+    }
+
+    /// Load a built-in theme by name, or else a custom theme from a RON file (not on the Web).
+    pub fn load(name: &str) -> Result<Self> {
+        std::cfg_select! {
+            target_arch = "wasm32" => Self::preset(name),
+            _ => Self::preset(name).or_else(|_| Self::parse(&std::fs::read_to_string(name)?)),
+        }
+    }
+
+    /// Next built-in theme, wrapping around (a custom theme is followed by the first built-in one).
+    pub fn next(&self) -> Self {
+        let index = PRESETS
+            .iter()
+            .position(|(preset, _)| *preset == self.name)
+            .map_or(0, |index| (index + 1) % PRESETS.len());
+
+        Self::preset(PRESETS[index].0).expect("The built-in themes are valid")
+    }
+
+    /// Colors of a category of buttons.
+    pub const fn button_colors(&self, kind: ButtonKind) -> &ButtonColors {
+        match kind {
+            ButtonKind::Digit => &self.digit,
+            ButtonKind::Operator => &self.operator,
+            ButtonKind::Function => &self.function,
+            ButtonKind::Clear => &self.clear,
+            ButtonKind::Equals => &self.equals,
+        }
+    }
+}
+
+/// Part of the UI colored by the theme, kept up to date when the theme changes.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThemeRole {
+    #[default]
+    Background,
+    Display,
+    DisplayText,
+    SecondaryText,
+    /// Label of a calculator button, colored after the button category
+    ButtonText,
+    Panel,
+    PanelText,
+}
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Theme>();
+        app.add_systems(Update, apply_theme);
+    }
+}
+
+/// Color the UI after the theme, when spawned or when the theme changes.
+#[allow(clippy::needless_pass_by_value, clippy::type_complexity)]
+fn apply_theme(
+    theme: Res<Theme>,
+    mut themed: Query<(
+        Ref<ThemeRole>,
+        Option<&ChildOf>,
+        Option<&mut BackgroundColor>,
+        Option<&mut BorderColor>,
+        Option<&mut TextColor>,
+    )>,
+    buttons: Query<&ButtonCategory>,
+) {
+    for (role, parent, background, border, text) in &mut themed {
+        if !theme.is_changed() && !role.is_added() {
+            continue;
+        }
+
+        let (background_color, border_color, text_color) = match *role {
+            ThemeRole::Background => (Some(theme.background), None, None),
+            ThemeRole::Display => (
+                Some(theme.display.background),
+                Some(theme.display.border),
+                None,
+            ),
+            ThemeRole::DisplayText => (None, None, Some(theme.display.text)),
+            ThemeRole::SecondaryText => (None, None, Some(theme.display.secondary_text)),
+            ThemeRole::ButtonText => {
+                let kind = parent
+                    .and_then(|parent| buttons.get(parent.parent()).ok())
+                    .map_or_else(ButtonKind::default, |category| category.0);
+                (None, None, Some(theme.button_colors(kind).text))
+            }
+            ThemeRole::Panel => (Some(theme.panel), None, None),
+            ThemeRole::PanelText => (None, None, Some(theme.function.text)),
+        };
+
+        if let (Some(mut background), Some(color)) = (background, background_color) {
+            background.0 = color;
+        }
+        if let (Some(mut border), Some(color)) = (border, border_color) {
+            *border = BorderColor::all(color);
+        }
+        if let (Some(mut text), Some(color)) = (text, text_color) {
+            text.0 = color;
+        }
+    }
+}
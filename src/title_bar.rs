@@ -0,0 +1,195 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::picking::pointer::PointerButton;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowLevel};
+
+use crate::localization::Localized;
+use crate::mini::{MiniMode, ToggleMiniMode};
+use crate::theme::{Theme, ThemeRole};
+
+/// Height of the title bar, in pixels.
+pub const TITLE_BAR_HEIGHT: f32 = 24.;
+
+/// An action of the title bar, replacing the window decorations.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowAction {
+    /// Switch between the standard and the mini mode
+    #[default]
+    Mini,
+    /// Keep the window above the other ones, or not
+    AlwaysOnTop,
+    Minimize,
+    Close,
+}
+
+impl WindowAction {
+    pub const ALL: [Self; 4] = [Self::Mini, Self::AlwaysOnTop, Self::Minimize, Self::Close];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Mini => "m",
+            Self::AlwaysOnTop => "^",
+            Self::Minimize => "_",
+            Self::Close => "x",
+        }
+    }
+}
+
+/// A button of the title bar, applying its window action when clicked.
+#[derive(Component, Default, Clone, Copy)]
+pub struct WindowControl(pub WindowAction);
+
+/// The part of the title bar moving the window when held.
+#[derive(Component, Default, Clone)]
+pub struct DragRegion;
+
+/// The title bar, since the window has no decorations (hidden on the Web, where the page hosting
+/// the calculator frames it).
+pub struct TitleBarPlugin;
+
+impl Plugin for TitleBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, window_controls_state);
+    }
+}
+
+/// The title bar, to be placed at the top of the window (hidden on the Web).
+pub fn title_bar() -> impl Scene {
+    let display = if cfg!(target_arch = "wasm32") {
+        Display::None
+    } else {
+        Display::Flex
+    };
+    let controls: Vec<Box<dyn Scene>> = WindowAction::ALL
+        .into_iter()
+        .map(|action| Box::new(control_scene(action)) as Box<dyn Scene>)
+        .collect();
+
+    bsn! {
+        Node {
+            display,
+            height: Val::Px(TITLE_BAR_HEIGHT),
+            flex_shrink: 0.,
+            align_items: AlignItems::Center,
+        }
+        Children [
+            (
+                Node {
+                    flex_grow: 1.,
+                    height: Val::Percent(100.),
+                    align_items: AlignItems::Center,
+                    padding: UiRect::left(Val::Px(8.)),
+                }
+                DragRegion
+                on(start_window_drag)
+                Children [(
+                    Localized("app-title")
+                    Text::default()
+                    TextFont::from_font_size(12.)
+                    TextColor::default()
+                    ThemeRole::SecondaryText
+                )]
+            ),
+            { controls },
+        ]
+    }
+}
+
+fn control_scene(action: WindowAction) -> impl Scene {
+    bsn! {
+        Button
+        WindowControl({action})
+        Node {
+            width: Val::Px(TITLE_BAR_HEIGHT),
+            height: Val::Px(TITLE_BAR_HEIGHT),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            border_radius: BorderRadius::MAX,
+        }
+        BackgroundColor::default()
+        on(on_window_control_click)
+        Children [(
+            Text({action.label()})
+            TextFont::from_font_size(12.)
+            TextColor::default()
+            ThemeRole::SecondaryText
+        )]
+    }
+}
+
+/// Move the window while the drag region is held.
+#[allow(clippy::needless_pass_by_value)]
+fn start_window_drag(
+    press: On<Pointer<Press>>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    if press.button == PointerButton::Primary {
+        window.start_drag_move();
+    }
+}
+
+/// Apply a window control when clicking it.
+#[allow(clippy::needless_pass_by_value)]
+fn on_window_control_click(
+    click: On<Pointer<Click>>,
+    controls: Query<&WindowControl>,
+    window: Option<Single<&mut Window, With<PrimaryWindow>>>,
+    mut toggles: MessageWriter<ToggleMiniMode>,
+    mut exit: MessageWriter<AppExit>,
+) -> Result {
+    let WindowControl(action) = *controls.get(click.entity)?;
+
+    debug!("Clicking on window control: {action:?}");
+
+    match action {
+        WindowAction::Mini => {
+            toggles.write(ToggleMiniMode);
+        }
+        WindowAction::AlwaysOnTop => {
+            let mut window = window.ok_or("No window to control")?;
+            window.window_level = if window.window_level == WindowLevel::AlwaysOnTop {
+                WindowLevel::Normal
+            } else {
+                WindowLevel::AlwaysOnTop
+            };
+        }
+        WindowAction::Minimize => window.ok_or("No window to control")?.set_minimized(true),
+        // Exit the app rather than closing the window, so the recorder sees it
+        WindowAction::Close => {
+            exit.write(AppExit::Success);
+        }
+    }
+
+    Ok(())
+}
+
+/// Highlight the hovered window controls, and the mini mode and always-on-top ones while active.
+#[allow(clippy::needless_pass_by_value)]
+fn window_controls_state(
+    mut controls: Query<(&WindowControl, &Interaction, &mut BackgroundColor)>,
+    window: Option<Single<&Window, With<PrimaryWindow>>>,
+    mini_mode: Res<MiniMode>,
+    theme: Res<Theme>,
+) {
+    let always_on_top =
+        window.is_some_and(|window| window.window_level == WindowLevel::AlwaysOnTop);
+
+    for (WindowControl(action), interaction, mut bg_color) in &mut controls {
+        let color = match (action, interaction) {
+            (WindowAction::Close, Interaction::Hovered | Interaction::Pressed) => {
+                theme.clear.normal
+            }
+            (_, Interaction::Hovered | Interaction::Pressed) => theme.function.hovered,
+            (WindowAction::Mini, Interaction::None) if mini_mode.is_enabled() => theme.focus,
+            (WindowAction::AlwaysOnTop, Interaction::None) if always_on_top => theme.focus,
+            (_, Interaction::None) => Color::NONE,
+        };
+        bg_color.set_if_neq(BackgroundColor(color));
+    }
+}
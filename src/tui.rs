@@ -0,0 +1,274 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::color::ColorToPacked;
+use std::io;
+
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, BorderType, Paragraph};
+
+use crate::button::{self, ButtonKind, ButtonVariant, DOT_BUTTON, EQUAL_BUTTON};
+use crate::engine::Calculator;
+use crate::format::NumberFormat;
+use crate::layout::{ButtonLayout, LayoutButton};
+use crate::locale::Locale;
+use crate::localization::Localization;
+use crate::settings::{self, Setting};
+use crate::theme::Theme;
+
+/// Size of a cell of the grid, in characters
+const CELL_WIDTH: u16 = 10;
+const CELL_HEIGHT: u16 = 3;
+
+/// State of the terminal frontend: the shared calculator engine plus the button focus.
+#[derive(Default)]
+struct TuiApp {
+    calculator: Calculator,
+    /// The buttons, laid out as the default layout of the GUI
+    layout: ButtonLayout,
+    /// Index of the focused button in the layout
+    focus: Option<usize>,
+    /// Whether the focus indicator is shown, like `InputFocusVisible` in the GUI
+    focus_visible: bool,
+    /// Last error raised by the engine, shown under the display until the next key press
+    error: Option<String>,
+    /// English texts of the display indicator, the terminal UI not being translated
+    localization: Localization,
+    /// Colors of the default theme of the GUI
+    theme: Theme,
+    exit: bool,
+}
+
+/// Run the calculator in the terminal until the user quits (`q` or `Ctrl+C`).
+pub fn run() -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let mut app = TuiApp {
+        calculator: Calculator::new(NumberFormat {
+            locale: Locale::detect(),
+            ..NumberFormat::default()
+        }),
+        ..TuiApp::default()
+    };
+    let result = app.run(&mut terminal);
+    ratatui::restore();
+
+    result
+}
+
+impl TuiApp {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.exit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        // The display row above the buttons
+        let area = center(
+            frame.area(),
+            self.layout.columns * CELL_WIDTH,
+            (self.layout.rows + 1) * CELL_HEIGHT,
+        );
+
+        let colors = &self.theme.display;
+        let display = Paragraph::new(self.calculator.display())
+            .centered()
+            .style(
+                Style::new()
+                    .fg(terminal_color(colors.text))
+                    .bg(terminal_color(colors.background)),
+            )
+            .block(
+                Block::bordered()
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::new().fg(terminal_color(colors.border)))
+                    .title_top(settings::localized_indicator(
+                        self.calculator.format(),
+                        &self.localization,
+                    ))
+                    .title_bottom(self.error.clone().unwrap_or_default()),
+            );
+        frame.render_widget(
+            display,
+            Rect {
+                height: CELL_HEIGHT,
+                ..area
+            },
+        );
+
+        for (index, button) in self.layout.buttons.iter().enumerate() {
+            let cell = Rect {
+                x: area.x + button.column * CELL_WIDTH,
+                y: area.y + (button.row + 1) * CELL_HEIGHT,
+                width: button.column_span * CELL_WIDTH,
+                height: button.row_span * CELL_HEIGHT,
+            }
+            .intersection(area);
+            frame.render_widget(self.button(index, button.action.0), cell);
+        }
+    }
+
+    /// Build a button widget, highlighted the same way `buttons_state` does in the GUI.
+    fn button(&self, index: usize, label: ButtonVariant) -> Paragraph<'static> {
+        let op_metadata = self.calculator.operation();
+        let is_current_operator = op_metadata.is_under_operation()
+            && op_metadata
+                .operator()
+                .is_some_and(|operator| ButtonVariant::from(operator) == label);
+        let is_focused = self.focus_visible && self.focus == Some(index);
+
+        // No hovered nor pressed states, the terminal having no pointer
+        let colors = self.theme.button_colors(ButtonKind::of(label));
+        let (background, border) = if is_current_operator {
+            (colors.normal, self.theme.highlight_border)
+        } else if is_focused {
+            (self.theme.focus, self.theme.focus_ring)
+        } else {
+            (colors.normal, self.theme.border)
+        };
+
+        let mut style = Style::new()
+            .fg(terminal_color(colors.text))
+            .bg(terminal_color(background));
+        if is_current_operator {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+
+        // The decimal button is labelled with the decimal separator of the locale
+        let label = if label == DOT_BUTTON {
+            self.calculator.format().decimal_separator().to_string()
+        } else {
+            label.to_string()
+        };
+
+        Paragraph::new(label).centered().style(style).block(
+            Block::bordered()
+                .border_type(BorderType::Rounded)
+                .border_style(Style::new().fg(terminal_color(border))),
+        )
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.error = None;
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.exit = true;
+            }
+            KeyCode::Char('q') => self.exit = true,
+            KeyCode::Esc => self.focus_visible = false,
+            KeyCode::Up => self.navigate(-1, 0),
+            KeyCode::Down => self.navigate(1, 0),
+            KeyCode::Left => self.navigate(0, -1),
+            KeyCode::Right => self.navigate(0, 1),
+            KeyCode::Enter => {
+                // Activate the focused button, or trigger EQUAL if no button is focused
+                let button = self
+                    .focus
+                    .and_then(|index| self.layout.buttons.get(index))
+                    .map_or(EQUAL_BUTTON, |button| button.action.0);
+                self.press(button);
+            }
+            KeyCode::Backspace => self.calculator.backspace(),
+            // F2 to F5 switch the precision, rounding mode, notation and maximum input length settings
+            KeyCode::F(key @ 2..=5) => {
+                Setting::ALL[usize::from(key - 2)].cycle(self.calculator.format_mut());
+            }
+            KeyCode::Char(c) => {
+                if let Some(button) = button::from_char(c) {
+                    self.press(button);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Move the focus to the closest button in the given direction, staying put on the grid edges.
+    fn navigate(&mut self, row_delta: isize, col_delta: isize) {
+        self.focus_visible = true;
+
+        // Same as the GUI: the first navigation only focuses the initial button
+        let Some(current) = self.focus else {
+            self.focus = Some(0);
+            return;
+        };
+        let Some(button) = self.layout.buttons.get(current) else {
+            return;
+        };
+
+        // Step cell by cell until reaching another button, e.g. over a double-width button
+        let (mut row, mut column) = (usize::from(button.row), usize::from(button.column));
+        loop {
+            let (Some(next_row), Some(next_column)) = (
+                row.checked_add_signed(row_delta),
+                column.checked_add_signed(col_delta),
+            ) else {
+                return;
+            };
+            if next_row >= usize::from(self.layout.rows)
+                || next_column >= usize::from(self.layout.columns)
+            {
+                return;
+            }
+            (row, column) = (next_row, next_column);
+
+            if let Some(index) = self
+                .layout
+                .buttons
+                .iter()
+                .position(|button| covers(button, row, column))
+                .filter(|index| *index != current)
+            {
+                self.focus = Some(index);
+                return;
+            }
+        }
+    }
+
+    fn press(&mut self, button: ButtonVariant) {
+        if let Err(err) = self.calculator.press(button) {
+            self.error = Some(err.to_string());
+        }
+    }
+}
+
+/// A color of the theme, in the terminal (its transparency being dropped).
+fn terminal_color(color: bevy::color::Color) -> Color {
+    let [red, green, blue] = color.to_srgba().to_u8_array_no_alpha();
+    Color::Rgb(red, green, blue)
+}
+
+/// Whether a button of the layout covers the given cell of the grid.
+fn covers(button: &LayoutButton, row: usize, column: usize) -> bool {
+    (usize::from(button.row)..usize::from(button.row + button.row_span)).contains(&row)
+        && (usize::from(button.column)..usize::from(button.column + button.column_span))
+            .contains(&column)
+}
+
+/// Center a fixed size rectangle in the given area.
+fn center(area: Rect, width: u16, height: u16) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .areas(area);
+
+    area
+}
@@ -0,0 +1,141 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::input::mouse::MouseScrollUnit;
+use bevy::prelude::*;
+use bevy::text::{EditableText, TextEditChange};
+
+use crate::operation::OperationMetadata;
+use crate::theme::ThemeRole;
+
+/// Font size of the display, when its content fits.
+pub const MAX_FONT_SIZE: f32 = 20.;
+/// Smallest font size of the display, the content is scrolled beyond.
+pub const MIN_FONT_SIZE: f32 = 11.;
+/// Width of a glyph of the (monospace) default font, relative to the font size.
+const GLYPH_WIDTH_RATIO: f32 = 0.6;
+/// Pixels scrolled per mouse wheel line.
+const SCROLL_LINE_HEIGHT: f32 = 20.;
+
+/// The clipping area of the display, scrolled horizontally once the content doesn't fit.
+#[derive(Component, Default, Clone)]
+pub struct DisplayViewport;
+
+/// Marker shown on a side of the display when some content is hidden on that side.
+#[derive(Component, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OverflowMarker {
+    /// Whether the marker is on the end (right) side, rather than the start (left) side
+    pub at_end: bool,
+}
+
+/// Fit long numbers in the display: shrink the font down to [`MIN_FONT_SIZE`], then scroll.
+pub struct ViewportPlugin;
+
+impl Plugin for ViewportPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (fit_display_font, update_overflow_markers));
+        app.add_observer(scroll_to_end);
+    }
+}
+
+/// Font size fitting a number of characters in a width, in pixels.
+pub fn fitted_font_size(width: f32, chars: usize) -> f32 {
+    #[allow(clippy::cast_precision_loss)]
+    let chars = chars.max(1) as f32;
+    (width / (chars * GLYPH_WIDTH_RATIO)).clamp(MIN_FONT_SIZE, MAX_FONT_SIZE)
+}
+
+/// A marker of hidden content, to be placed on a side of the display.
+pub fn overflow_marker(at_end: bool) -> impl Scene {
+    let (label, left, right) = if at_end {
+        (">", Val::Auto, Val::Px(12.))
+    } else {
+        ("<", Val::Px(12.), Val::Auto)
+    };
+
+    bsn! {
+        Node {
+            position_type: PositionType::Absolute,
+            left,
+            right,
+        }
+        OverflowMarker { at_end }
+        Visibility::Hidden
+        Text({label})
+        TextColor::default()
+        ThemeRole::SecondaryText
+    }
+}
+
+/// Shrink the display font as its content grows.
+#[allow(clippy::needless_pass_by_value)]
+fn fit_display_font(
+    viewport: Single<&ComputedNode, With<DisplayViewport>>,
+    mut display: Single<(&EditableText, &mut TextFont), With<OperationMetadata>>,
+) {
+    let width = viewport.size().x * viewport.inverse_scale_factor();
+    // Not laid out yet
+    if width <= 0. {
+        return;
+    }
+
+    let (editable, font) = &mut *display;
+    let font_size = fitted_font_size(width, editable.value().to_string().chars().count());
+    if (font.font_size - font_size).abs() >= 0.5 {
+        font.font_size = font_size;
+    }
+}
+
+/// Keep the end of the display, where the digits are typed, visible.
+fn scroll_to_end(
+    _change: On<TextEditChange>,
+    mut viewport: Single<&mut ScrollPosition, With<DisplayViewport>>,
+) {
+    // Clamped to the content width by the UI layout
+    viewport.x = f32::MAX;
+}
+
+/// Scroll the display with the mouse wheel, either vertical or horizontal.
+pub fn scroll_display(
+    scroll: On<Pointer<Scroll>>,
+    mut viewport: Single<(&mut ScrollPosition, &ComputedNode), With<DisplayViewport>>,
+) {
+    let (position, node) = &mut *viewport;
+    let line_height = match scroll.unit {
+        MouseScrollUnit::Line => SCROLL_LINE_HEIGHT,
+        MouseScrollUnit::Pixel => 1.,
+    };
+    let max_scroll = (node.content_size().x - node.size().x).max(0.) * node.inverse_scale_factor();
+
+    position.x = (position.x - (scroll.x + scroll.y) * line_height).clamp(0., max_scroll);
+}
+
+/// Show the overflow markers on the sides of the display hiding some content.
+#[allow(clippy::needless_pass_by_value)]
+fn update_overflow_markers(
+    viewport: Single<(&ScrollPosition, &ComputedNode), With<DisplayViewport>>,
+    mut markers: Query<(&OverflowMarker, &mut Visibility)>,
+) {
+    let (position, node) = *viewport;
+    let max_scroll = (node.content_size().x - node.size().x).max(0.) * node.inverse_scale_factor();
+    // Not clamped yet by the UI layout, after scrolling to the end
+    let position = position.x.min(max_scroll);
+
+    for (marker, mut visibility) in &mut markers {
+        let hides_content = if marker.at_end {
+            position < max_scroll - 0.5
+        } else {
+            position > 0.5
+        };
+
+        visibility.set_if_neq(if hides_content {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
@@ -0,0 +1,160 @@
+/*
+ *
+ * Copyright (c) Jérémy Audiger.
+ * All rights reserved.
+ *
+ */
+
+use bevy::prelude::*;
+use bevy::text::EditableText;
+use std::cell::RefCell;
+use wasm_bindgen::prelude::*;
+
+use crate::action::{CalcAction, CalcResult, apply_calc_actions};
+use crate::button;
+use crate::expression;
+use crate::operation::OperationMetadata;
+
+thread_local! {
+    /// Actions requested by the host page, forwarded to the app on the next frame.
+    static PENDING_ACTIONS: RefCell<Vec<CalcAction>> = const { RefCell::new(Vec::new()) };
+    /// Callbacks registered by the host page with `onResult`.
+    static RESULT_CALLBACKS: RefCell<Vec<js_sys::Function>> = const { RefCell::new(Vec::new()) };
+    /// Last known calculator state, returned by `getState`.
+    static STATE: RefCell<CalcState> = RefCell::new(CalcState::default());
+}
+
+/// Snapshot of the calculator, mirrored from the app every frame.
+#[derive(Clone)]
+struct CalcState {
+    display: String,
+    left_operand: Option<f64>,
+    operator: Option<String>,
+    right_operand: Option<f64>,
+}
+
+impl Default for CalcState {
+    fn default() -> Self {
+        Self {
+            display: "0".to_string(),
+            left_operand: None,
+            operator: None,
+            right_operand: None,
+        }
+    }
+}
+
+/// Expose the calculator to the host page (see `index.html`).
+pub struct WebPlugin;
+
+impl Plugin for WebPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                forward_web_actions.before(apply_calc_actions),
+                (notify_web_results, update_web_state).after(apply_calc_actions),
+            ),
+        );
+    }
+}
+
+/// Handle to drive the calculator from JavaScript, returned by `calculator()`.
+#[wasm_bindgen(js_name = Calculator)]
+pub struct JsCalculator;
+
+/// Get a handle on the calculator running in the page.
+#[wasm_bindgen]
+pub fn calculator() -> JsCalculator {
+    JsCalculator
+}
+
+#[wasm_bindgen(js_class = Calculator)]
+impl JsCalculator {
+    /// Evaluate an expression such as `12 * (3 + 4)`, show its result on the display and return it.
+    pub fn evaluate(&self, expr: &str) -> Result<f64, JsError> {
+        let value = expression::evaluate(expr).map_err(|err| JsError::new(&err.to_string()))?;
+        queue_action(CalcAction::Evaluate(expr.to_string()));
+
+        Ok(value)
+    }
+
+    /// Press a button given its label, e.g. `7`, `+`, `=` or `C`.
+    pub fn press(&self, action: &str) -> Result<(), JsError> {
+        let button = button::from_label(action)
+            .ok_or_else(|| JsError::new(&format!("Unknown calculator action: {action}")))?;
+        queue_action(CalcAction::Press(button));
+
+        Ok(())
+    }
+
+    /// Register a callback called with `{ expression, value }` every time a result is computed.
+    #[wasm_bindgen(js_name = onResult)]
+    pub fn on_result(&self, callback: js_sys::Function) {
+        RESULT_CALLBACKS.with_borrow_mut(|callbacks| callbacks.push(callback));
+    }
+
+    /// Get the calculator state as `{ display, leftOperand, operator, rightOperand }`.
+    #[wasm_bindgen(js_name = getState)]
+    pub fn get_state(&self) -> Result<JsValue, JsValue> {
+        let state = STATE.with_borrow(Clone::clone);
+
+        let object = js_sys::Object::new();
+        js_sys::Reflect::set(&object, &"display".into(), &state.display.into())?;
+        js_sys::Reflect::set(&object, &"leftOperand".into(), &state.left_operand.into())?;
+        js_sys::Reflect::set(&object, &"operator".into(), &state.operator.into())?;
+        js_sys::Reflect::set(&object, &"rightOperand".into(), &state.right_operand.into())?;
+
+        Ok(object.into())
+    }
+}
+
+fn queue_action(action: CalcAction) {
+    PENDING_ACTIONS.with_borrow_mut(|actions| actions.push(action));
+}
+
+/// Forward the actions requested by the host page to the app.
+fn forward_web_actions(mut actions: MessageWriter<CalcAction>) {
+    PENDING_ACTIONS.with_borrow_mut(|pending_actions| {
+        actions.write_batch(pending_actions.drain(..));
+    });
+}
+
+/// Call the host page callbacks for every computed result.
+fn notify_web_results(mut results: MessageReader<CalcResult>) -> Result {
+    for CalcResult(evaluation) in results.read() {
+        let object = js_sys::Object::new();
+        js_sys::Reflect::set(
+            &object,
+            &"expression".into(),
+            &evaluation.expression.as_str().into(),
+        )
+        .map_err(|_| "Failed to build the result object")?;
+        js_sys::Reflect::set(&object, &"value".into(), &evaluation.value.into())
+            .map_err(|_| "Failed to build the result object")?;
+
+        RESULT_CALLBACKS.with_borrow(|callbacks| {
+            for callback in callbacks {
+                if let Err(err) = callback.call1(&JsValue::NULL, &object) {
+                    warn!("Result callback failed: {err:?}");
+                }
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Mirror the calculator state, so it can be read synchronously by the host page.
+fn update_web_state(display: Single<(&EditableText, &OperationMetadata)>) {
+    let (editable, op_metadata) = *display;
+
+    STATE.with_borrow_mut(|state| {
+        *state = CalcState {
+            display: editable.value().to_string(),
+            left_operand: op_metadata.left_operand(),
+            operator: op_metadata.operator().map(|operator| operator.to_string()),
+            right_operand: op_metadata.right_operand(),
+        };
+    });
+}